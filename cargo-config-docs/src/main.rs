@@ -0,0 +1,219 @@
+// cargo-config-docs/src/main.rs
+//! `cargo config-docs`: generate the docs of every exported config type in a
+//! crate or workspace, without going through `cargo test`
+//!
+//! Config types can only be listed by code that links them, so this writes a
+//! small runner crate that depends on every crate using serde-config-docs,
+//! with the `registry` feature on, and runs it to export the docs.
+
+mod runner;
+
+use cargo_metadata::{DependencyKind, Metadata, MetadataCommand, Package};
+use clap::Parser;
+use serde_config_docs::ConfigFormat;
+
+use std::path::PathBuf;
+use std::process;
+
+use runner::Runner;
+
+#[derive(Parser)]
+#[command(name = "cargo", bin_name = "cargo")]
+enum Cli {
+    ConfigDocs(Args),
+}
+
+/// Generate Markdown docs for the config types exported by a crate or
+/// workspace
+#[derive(clap::Args)]
+#[command(version)]
+struct Args {
+    /// Path to the Cargo.toml of the crate or workspace
+    #[arg(long, value_name = "PATH")]
+    manifest_path: Option<PathBuf>,
+    /// Only document these packages of the workspace
+    #[arg(short, long = "package", value_name = "NAME")]
+    packages: Vec<String>,
+    /// Formats to write every type's examples in, each to its own file,
+    /// instead of the formats each type exports. Defaults to toml with
+    /// --out-dir.
+    #[arg(short, long = "format", value_name = "FORMAT")]
+    formats: Vec<String>,
+    /// Directory to write every type's docs to, relative to the workspace
+    /// root, instead of where each type exports them. Defaults to docs with
+    /// --format.
+    #[arg(short, long, value_name = "DIR")]
+    out_dir: Option<PathBuf>,
+    /// Fail if the docs on disk are out of date instead of writing them
+    #[arg(long)]
+    check: bool,
+}
+
+fn main() {
+    let Cli::ConfigDocs(args) = Cli::parse();
+
+    match run(&args) {
+        Ok(success) => process::exit(if success { 0 } else { 1 }),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(2);
+        }
+    }
+}
+
+/// Export the docs, returning whether every file was written or up to date
+fn run(args: &Args) -> Result<bool, String> {
+    let mut command = MetadataCommand::new();
+    if let Some(path) = &args.manifest_path {
+        command.manifest_path(path);
+    }
+    let metadata = command.exec().map_err(|err| err.to_string())?;
+
+    let library = metadata
+        .packages
+        .iter()
+        .find(|package| package.name == "serde-config-docs")
+        .ok_or("nothing in this workspace depends on serde-config-docs")?;
+    let packages = documented_packages(&metadata, &args.packages)?;
+    if packages.is_empty() {
+        return Err("none of the selected packages depend on serde-config-docs".to_string());
+    }
+
+    // With neither option, each type is exported as its own attributes say,
+    // and the packages enable the features of the formats they list
+    let global = !args.formats.is_empty() || args.out_dir.is_some();
+    let formats = match args.formats.as_slice() {
+        [] => vec!["toml".to_string()],
+        formats => formats.to_vec(),
+    };
+    let out_dir = metadata
+        .workspace_root
+        .as_std_path()
+        .join(args.out_dir.as_deref().unwrap_or("docs".as_ref()));
+
+    // Only built-in formats can be named here, as the runner can't know about
+    // renderers registered by the crates themselves
+    let mut features = vec!["registry"];
+    if global {
+        for name in &formats {
+            let feature = ConfigFormat::feature_for(name)
+                .ok_or_else(|| format!("`{}` isn't a built-in format", name))?;
+            if !features.contains(&feature) {
+                features.push(feature);
+            }
+        }
+    }
+
+    let runner = Runner::new(&metadata);
+    runner.write(library, &packages, &features)?;
+    runner.run(
+        global.then_some((out_dir.as_path(), formats.as_slice())),
+        args.check,
+    )
+}
+
+/// The workspace members to document: the ones named with `--package`, or
+/// else every member with a library that depends on serde-config-docs
+fn documented_packages<'a>(
+    metadata: &'a Metadata,
+    names: &[String],
+) -> Result<Vec<&'a Package>, String> {
+    let members = metadata.workspace_packages();
+
+    for name in names {
+        if !members.iter().any(|package| package.name == *name) {
+            return Err(format!("`{}` isn't a member of this workspace", name));
+        }
+    }
+
+    Ok(members
+        .into_iter()
+        .filter(|package| names.is_empty() || names.contains(&package.name))
+        .filter(|package| package.name != "serde-config-docs")
+        // Only the library's own code can export docs, so a dev- or
+        // build-dependency on the library doesn't count
+        .filter(|package| {
+            package.dependencies.iter().any(|dependency| {
+                dependency.name == "serde-config-docs" && dependency.kind == DependencyKind::Normal
+            })
+        })
+        .filter(|package| package.targets.iter().any(|target| target.is_lib()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn parse(args: &[&str]) -> Args {
+        let Cli::ConfigDocs(args) = Cli::try_parse_from(args).unwrap();
+        args
+    }
+
+    #[test]
+    fn cli_is_well_formed() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn formats_and_out_dir_are_optional() {
+        let args = parse(&["cargo", "config-docs"]);
+        assert!(args.formats.is_empty());
+        assert!(args.out_dir.is_none());
+        assert!(!args.check);
+
+        let args = parse(&[
+            "cargo",
+            "config-docs",
+            "-f",
+            "toml",
+            "--format",
+            "yaml",
+            "-o",
+            "book",
+            "-p",
+            "demo",
+            "--check",
+        ]);
+        assert_eq!(args.formats, ["toml", "yaml"]);
+        assert_eq!(args.out_dir, Some(PathBuf::from("book")));
+        assert_eq!(args.packages, ["demo"]);
+        assert!(args.check);
+    }
+
+    #[test]
+    fn only_packages_using_the_library_are_documented() {
+        let metadata = MetadataCommand::new()
+            .manifest_path(concat!(env!("CARGO_MANIFEST_DIR"), "/../Cargo.toml"))
+            .exec()
+            .unwrap();
+        let names = |packages: Vec<&Package>| -> Vec<String> {
+            packages
+                .iter()
+                .map(|package| package.name.to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            names(documented_packages(&metadata, &[]).unwrap()),
+            ["demo"]
+        );
+        // The CLI itself only has a binary
+        let selected = documented_packages(&metadata, &["cargo-config-docs".to_string()]);
+        assert!(selected.unwrap().is_empty());
+
+        let err = documented_packages(&metadata, &["missing".to_string()]).unwrap_err();
+        assert_eq!(err, "`missing` isn't a member of this workspace");
+
+        let mut metadata = metadata;
+        for package in &mut metadata.packages {
+            for dependency in &mut package.dependencies {
+                if dependency.name == "serde-config-docs" {
+                    dependency.kind = DependencyKind::Development;
+                }
+            }
+        }
+        assert!(documented_packages(&metadata, &[]).unwrap().is_empty());
+    }
+}
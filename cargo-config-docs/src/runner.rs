@@ -0,0 +1,375 @@
+// cargo-config-docs/src/runner.rs
+//! The crate that links every documented package and exports their docs
+//!
+//! It lives in the target directory and is rewritten on every run, and it
+//! starts from a copy of the workspace's lockfile so it builds against the
+//! same dependencies as the workspace does.
+
+use cargo_metadata::{Metadata, Package};
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The runner's `main`, after the `extern crate` lines that link the
+/// documented packages
+///
+/// Given formats and an output directory, it documents every registered type
+/// with those. Otherwise each type is exported the way its own attributes
+/// say, as its generated test does.
+const MAIN: &str = r#"
+use serde_config_docs::{
+    export_docs, export_registered_docs, registered_configs, DocsMode, MarkdownOptions,
+};
+
+use std::path::PathBuf;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let check = args.next().as_deref() == Some("check");
+    let mode = if check { DocsMode::Check } else { DocsMode::Write };
+    let out_dir = args.next();
+    let formats: Vec<String> = args.collect();
+
+    if registered_configs().is_empty() {
+        eprintln!("warning: no exported config types were found");
+    }
+
+    let mut success = true;
+    let mut report = |result: Result<Vec<PathBuf>, String>| match result {
+        Ok(paths) => {
+            for path in paths {
+                match mode {
+                    DocsMode::Write => println!("Wrote {}", path.display()),
+                    DocsMode::Check => println!("Checked {}", path.display()),
+                }
+            }
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            success = false;
+        }
+    };
+
+    match out_dir {
+        Some(out_dir) => {
+            for name in formats {
+                let format = match name.parse() {
+                    Ok(format) => format,
+                    Err(err) => {
+                        eprintln!("error: {}", err);
+                        std::process::exit(2);
+                    }
+                };
+                let options = MarkdownOptions::new(format);
+                report(export_registered_docs(&out_dir, &options, mode).map_err(|err| err.to_string()));
+            }
+        }
+        None => {
+            for config in registered_configs() {
+                let Some(settings) = &config.export else {
+                    eprintln!(
+                        "note: `{}` has no export settings, so it's only documented with --format or --out-dir",
+                        config.path()
+                    );
+                    continue;
+                };
+                let mode = if check { DocsMode::Check } else { settings.mode() };
+                let result = match settings.formats() {
+                    Ok(formats) => export_docs(config.manifest_dir, config.schema, settings, &formats, mode)
+                        .map_err(|err| format!("{}: {}", config.path(), err)),
+                    Err(err) => Err(format!("{}: {}", config.path(), err)),
+                };
+                report(result);
+            }
+        }
+    }
+
+    std::process::exit(if success { 0 } else { 1 });
+}
+"#;
+
+/// The sources cargo reports for crates.io packages
+const CRATES_IO: [&str; 2] = [
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
+
+/// A runner crate in the workspace's target directory
+pub struct Runner {
+    dir: PathBuf,
+    workspace_root: PathBuf,
+}
+
+impl Runner {
+    pub fn new(metadata: &Metadata) -> Self {
+        Runner {
+            dir: metadata.target_directory.as_std_path().join("config-docs"),
+            workspace_root: metadata.workspace_root.clone().into_std_path_buf(),
+        }
+    }
+
+    /// Write the runner's manifest and source for the documented packages
+    pub fn write(
+        &self,
+        library: &Package,
+        packages: &[&Package],
+        features: &[&str],
+    ) -> Result<(), String> {
+        let mut manifest = String::from(
+            "[package]\n\
+             name = \"config-docs-runner\"\n\
+             version = \"0.0.0\"\n\
+             edition = \"2021\"\n\
+             publish = false\n\
+             \n\
+             # Keep the runner out of any workspace above the target directory\n\
+             [workspace]\n\
+             \n\
+             [dependencies]\n",
+        );
+        let mut main = String::from("// Generated by cargo-config-docs\n");
+
+        for package in packages {
+            let crate_name = package.name.replace('-', "_");
+            writeln!(
+                manifest,
+                "{} = {{ package = {:?}, path = {:?} }}",
+                crate_name,
+                package.name,
+                package_dir(package)
+            )
+            .unwrap();
+            writeln!(main, "extern crate {};", crate_name).unwrap();
+        }
+
+        // Use the same serde-config-docs as the workspace, so the runner and
+        // the packages share one registry
+        let source = dependency_source(
+            library.source.as_ref().map(|source| source.repr.as_str()),
+            &library.version.to_string(),
+            package_dir(library),
+            |index| registry_name(index, &self.workspace_root),
+        )?;
+        writeln!(
+            manifest,
+            "serde-config-docs = {{ {}, features = {:?} }}",
+            source, features
+        )
+        .unwrap();
+        main.push_str(MAIN);
+
+        let src = self.dir.join("src");
+        fs::create_dir_all(&src).map_err(|err| format!("{}: {}", src.display(), err))?;
+        write(&self.dir.join("Cargo.toml"), &manifest)?;
+        write(&src.join("main.rs"), &main)?;
+
+        let lockfile = self.workspace_root.join("Cargo.lock");
+        if lockfile.exists() {
+            let copy = self.dir.join("Cargo.lock");
+            fs::copy(&lockfile, &copy).map_err(|err| format!("{}: {}", copy.display(), err))?;
+        }
+        Ok(())
+    }
+
+    /// Build and run the runner, returning whether every file was written or
+    /// up to date
+    ///
+    /// With an output directory and formats, every type is documented with
+    /// those. Otherwise each type is exported as its own attributes say.
+    pub fn run(&self, global: Option<(&Path, &[String])>, check: bool) -> Result<bool, String> {
+        // Cargo tells subcommands which cargo invoked them
+        let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+        let mut command = Command::new(cargo);
+        command
+            .arg("run")
+            .arg("--quiet")
+            .arg("--manifest-path")
+            .arg(self.dir.join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(self.dir.join("target"))
+            // Run from the workspace, so its Cargo config and registries apply
+            .current_dir(&self.workspace_root)
+            .arg("--")
+            .arg(if check { "check" } else { "write" });
+        if let Some((out_dir, formats)) = global {
+            command.arg(out_dir).args(formats);
+        }
+        let status = command
+            .status()
+            .map_err(|err| format!("couldn't run cargo: {}", err))?;
+
+        match status.code() {
+            Some(0) => Ok(true),
+            Some(1) => Ok(false),
+            _ => Err("couldn't build or run the docs runner".to_string()),
+        }
+    }
+}
+
+/// The fields of a dependency on a package from `source`, as cargo metadata
+/// reports it, e.g. `git = "...", rev = "..."`
+///
+/// Registry packages are pinned to their exact version, and git ones to the
+/// commit in the lockfile. `registry_name` finds the name Cargo's config
+/// gives a registry other than crates.io.
+fn dependency_source(
+    source: Option<&str>,
+    version: &str,
+    dir: &Path,
+    registry_name: impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let source = match source {
+        Some(source) => source,
+        None => return Ok(format!("path = {:?}", dir)),
+    };
+
+    // e.g. `git+https://github.com/org/repo?branch=main#<commit>`
+    if let Some(git) = source.strip_prefix("git+") {
+        let (url, commit) = git
+            .split_once('#')
+            .ok_or_else(|| format!("the git source `{}` has no commit", source))?;
+        let url = url.split('?').next().unwrap_or(url);
+        return Ok(format!("git = {:?}, rev = {:?}", url, commit));
+    }
+
+    let version = format!("version = \"={}\"", version);
+    if CRATES_IO.contains(&source) {
+        return Ok(version);
+    }
+    if source.starts_with("registry+") || source.starts_with("sparse+") {
+        let name = registry_name(source).ok_or_else(|| {
+            format!(
+                "serde-config-docs comes from the registry `{}`, which isn't named in any Cargo config",
+                source
+            )
+        })?;
+        return Ok(format!("{}, registry = {:?}", version, name));
+    }
+
+    Err(format!(
+        "serde-config-docs comes from `{}`, which the runner can't depend on",
+        source
+    ))
+}
+
+/// The name of the registry with the index in `source`, from the
+/// `CARGO_REGISTRIES_<NAME>_INDEX` variables or the Cargo config files that
+/// apply to the workspace
+fn registry_name(source: &str, workspace_root: &Path) -> Option<String> {
+    // Git indexes are configured without the `registry+`, sparse ones with
+    // their `sparse+`
+    let index = source.strip_prefix("registry+").unwrap_or(source);
+
+    let from_env = env::vars().find_map(|(key, value)| {
+        let name = key
+            .strip_prefix("CARGO_REGISTRIES_")?
+            .strip_suffix("_INDEX")?;
+        (value == index).then(|| name.to_lowercase().replace('_', "-"))
+    });
+    if from_env.is_some() {
+        return from_env;
+    }
+
+    let cargo_home = env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cargo")));
+    let config_dirs = workspace_root
+        .ancestors()
+        .map(|dir| dir.join(".cargo"))
+        .chain(cargo_home);
+
+    config_dirs
+        .flat_map(|dir| [dir.join("config.toml"), dir.join("config")])
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|config| config.parse::<toml::Table>().ok())
+        .find_map(|config| {
+            let registries = config.get("registries")?.as_table()?;
+            registries.iter().find_map(|(name, registry)| {
+                (registry.get("index")?.as_str()? == index).then(|| name.clone())
+            })
+        })
+}
+
+/// The directory holding a package's manifest
+fn package_dir(package: &Package) -> &Path {
+    package
+        .manifest_path
+        .parent()
+        .expect("manifest path has no parent")
+        .as_std_path()
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(source: Option<&str>) -> Result<String, String> {
+        dependency_source(
+            source,
+            "0.1.0",
+            Path::new("/src/serde-config-docs"),
+            |index| (index == "sparse+https://crates.example.com/").then(|| "example".to_string()),
+        )
+    }
+
+    #[test]
+    fn path_packages_are_linked_by_path() {
+        assert_eq!(source(None).unwrap(), "path = \"/src/serde-config-docs\"");
+    }
+
+    #[test]
+    fn registry_packages_are_pinned_to_their_version() {
+        for crates_io in CRATES_IO {
+            assert_eq!(source(Some(crates_io)).unwrap(), "version = \"=0.1.0\"");
+        }
+        assert_eq!(
+            source(Some("sparse+https://crates.example.com/")).unwrap(),
+            "version = \"=0.1.0\", registry = \"example\""
+        );
+        assert!(source(Some("registry+https://unknown.example.com/index"))
+            .unwrap_err()
+            .contains("isn't named in any Cargo config"));
+    }
+
+    #[test]
+    fn git_packages_are_pinned_to_their_commit() {
+        assert_eq!(
+            source(Some("git+https://github.com/org/repo?branch=main#0123abc")).unwrap(),
+            "git = \"https://github.com/org/repo\", rev = \"0123abc\""
+        );
+        assert_eq!(
+            source(Some("git+https://github.com/org/repo#0123abc")).unwrap(),
+            "git = \"https://github.com/org/repo\", rev = \"0123abc\""
+        );
+        assert!(source(Some("git+https://github.com/org/repo")).is_err());
+    }
+
+    #[test]
+    fn registries_are_found_in_cargo_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("workspace");
+        fs::create_dir_all(workspace.join(".cargo")).unwrap();
+        fs::write(
+            workspace.join(".cargo/config.toml"),
+            "[registries.company]\nindex = \"https://git.example.com/index\"\n\n\
+             [registries.sparse]\nindex = \"sparse+https://crates.example.com/\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            registry_name("registry+https://git.example.com/index", &workspace).as_deref(),
+            Some("company")
+        );
+        assert_eq!(
+            registry_name("sparse+https://crates.example.com/", &workspace).as_deref(),
+            Some("sparse")
+        );
+    }
+}
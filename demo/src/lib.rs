@@ -2,13 +2,13 @@ use serde::{Deserialize, Serialize};
 use serde_config_docs::ConfigDocs;
 
 #[derive(Serialize, Deserialize, ConfigDocs)]
-#[config_docs(export)]
-struct Config {
+#[config_docs(export(pages), env_prefix = "STREAMLIT")]
+pub struct Config {
     global: Global,
 }
 
 #[derive(Serialize, Deserialize, ConfigDocs)]
-struct Global {
+pub struct Global {
     /// By default, Streamlit displays a warning when a user sets both a widget
     /// default value in the function defining the widget and a widget value via
     /// the widget's key in `st.session_state`.
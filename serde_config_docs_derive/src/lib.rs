@@ -1,44 +1,64 @@
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
 use syn::{
-    parse_macro_input, Attribute, Data, DeriveInput, ExprPath, Field, Fields, Lit, Meta, MetaNameValue, NestedMeta, Type
+    bracketed, parenthesized, parse_macro_input, token, Attribute, Data, DeriveInput, ExprPath, Field, Fields, Ident, Lit, LitStr, Meta, MetaNameValue, NestedMeta, Token, Type
 };
 
-#[proc_macro_derive(ConfigDocs, attributes(serde, doc, config_docs))]
+#[proc_macro_derive(ConfigDocs, attributes(serde, config_docs))]
 pub fn derive_config_docs(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
+    match expand(&input) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     // Extract struct name
     let struct_name = &input.ident;
     let struct_name_str = struct_name.to_string();
 
-    // Check if export is enabled
-    let should_export = input.attrs.iter().any(|attr| {
-        if attr.path.is_ident("config_docs") {
-            if let Ok(Meta::List(meta_list)) = attr.parse_meta() {
-                for nested in meta_list.nested.iter() {
-                    if let NestedMeta::Meta(Meta::Path(path)) = nested {
-                        if path.is_ident("export") {
-                            return true;
-                        }
-                    }
-                }
-            }
-            return false;
-        }
-        false
-    });
+    // Extract our own struct-level attributes
+    let attrs = StructAttrs::parse(&input.attrs)?;
+
+    // Check if export is enabled, as `export` or `export(check)`
+    let export = attrs.export;
+    let should_export = export.is_some();
 
-    // Extract struct-level rename_all
+    // Extract struct-level rename_all and default
     let rename_all = extract_rename_all(&input.attrs);
+    let struct_default = extract_default(&input.attrs).is_some();
+
+    let env_prefix_tokens = match &attrs.env_prefix {
+        Some(prefix) => quote! { .env_prefix(#prefix) },
+        None => quote! {},
+    };
+
+    // A Markdown file to keep the docs up to date in, relative to the crate
+    let inject_path = attrs.inject;
 
     // Process fields
     let fields_tokens = match &input.data {
         Data::Struct(data_struct) => match &data_struct.fields {
-            Fields::Named(fields) => process_fields(&fields.named, &rename_all),
-            _ => panic!("Only named fields are supported"),
+            Fields::Named(fields) => {
+                process_fields(&fields.named, &rename_all, struct_name, struct_default)?
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    struct_name,
+                    "ConfigDocs only supports structs with named fields",
+                ))
+            }
         },
-        _ => panic!("ConfigDocs can only be derived for structs"),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                struct_name,
+                "ConfigDocs can only be derived for structs",
+            ))
+        }
     };
 
     // Generate the trait implementation
@@ -46,64 +66,120 @@ pub fn derive_config_docs(input: TokenStream) -> TokenStream {
         impl serde_config_docs::ConfigDocsStruct for #struct_name {
             fn schema() -> serde_config_docs::ConfigSchema {
                 serde_config_docs::ConfigSchema::builder()
+                    .name(#struct_name_str)
+                    #env_prefix_tokens
                     #fields_tokens
                     .build()
             }
         }
     };
 
+    // How the docs are exported, as set by `export(...)` and `inject`
+    let export = export.unwrap_or_default();
+    let path_tokens = option_tokens(&export.path);
+    let title_tokens = option_tokens(&export.title);
+    let inject_tokens = option_tokens(&inject_path);
+    let format_names = &export.formats;
+    let pages = export.pages;
+    let check = export.check;
+
+    // Other root types whose docs follow this struct's in the same document
+    let combined = export
+        .combine
+        .iter()
+        .map(|name| {
+            name.parse::<syn::Path>().map_err(|_| {
+                let message = format!("`{}` in `combine` isn't a type", name.value());
+                syn::Error::new(name.span(), message)
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let export_settings = quote! {
+        serde_config_docs::ExportSettings {
+            files: #should_export,
+            path: #path_tokens,
+            formats: &[#(#format_names),*],
+            title: #title_tokens,
+            pages: #pages,
+            check: #check,
+            combine: &[
+                #(<#combined as serde_config_docs::ConfigDocsStruct>::schema as fn() -> serde_config_docs::ConfigSchema),*
+            ],
+            inject: #inject_tokens,
+        }
+    };
+
     // If export is enabled, also generate a test function
-    let test_fn = if should_export {
+    let test_fn = if should_export || inject_path.is_some() {
         let test_name = format_ident!("export_serde_docs_{}", struct_name_str.to_lowercase());
+        // Named after the struct, so several exported structs can share a module
+        let module_name = format_ident!("config_docs_tests_{}", struct_name_str.to_lowercase());
 
         quote! {
             #[cfg(test)]
-            mod config_docs_tests {
+            mod #module_name {
                 use super::*;
                 
                 #[test]
                 fn #test_name() {
-                    use std::path::Path;
-                    use std::fs;
-                    use std::fs::File;
-                    use std::io::Write;
-                    use std::env;
-
-                    // Get format from environment variable or default to "toml"
-                    let format_str = env::var("CONFIG_DOCS_FORMAT").unwrap_or_else(|_| "toml".to_string());
-                    
-                    // Parse format string
-                    let format = match format_str.to_lowercase().as_str() {
-                        // #[cfg(toml)]
-                        "toml" => serde_config_docs::ConfigFormat::Toml,
-                        _ => {
-                            unimplemented!("Unsupported format '{}'", format_str);
-                        }
+                    const SETTINGS: serde_config_docs::ExportSettings = #export_settings;
+
+                    // Export the formats listed in the attribute, unless the environment
+                    // variable picks one, defaulting to the first compiled-in one
+                    let formats = match SETTINGS.formats() {
+                        Ok(formats) => formats,
+                        Err(err) => panic!("export formats: {}", err),
                     };
-                    
-                    // Generate file name based on format
-                    let file_name = format!("{}.{}.md", #struct_name_str, format.extension());
-                    
-                    let options = serde_config_docs::MarkdownOptions::new(format);
-                        
-                    let docs = serde_config_docs::generate_config_docs_with_options::<#struct_name>(&options);
-                    
-                    let file_path = Path::new(env!("CARGO_MANIFEST_DIR"))
-                        .join("docs")
-                        .join(file_name);
-                    
-                    // Create docs directory if it doesn't exist
-                    if let Some(parent) = file_path.parent() {
-                        fs::create_dir_all(parent).expect("Failed to create docs directory");
+
+                    // Write the docs, or only compare them in check mode
+                    let schema = <#struct_name as serde_config_docs::ConfigDocsStruct>::schema;
+                    match serde_config_docs::export_docs(env!("CARGO_MANIFEST_DIR"), schema, &SETTINGS, &formats, SETTINGS.mode()) {
+                        Ok(paths) => {
+                            for path in paths {
+                                println!("Generated documentation: {}", path.display());
+                            }
+                        }
+                        Err(err) => panic!("{}", err),
+                    }
+
+                    // Make sure the documented example actually loads as this struct
+                    for &format in &formats {
+                        for defaults in [
+                            serde_config_docs::DefaultsStyle::Explicit,
+                            serde_config_docs::DefaultsStyle::CommentedOut,
+                        ] {
+                            let options = serde_config_docs::ExampleOptions::new(format).defaults(defaults);
+
+                            if let Err(err) = serde_config_docs::verify_example_config::<#struct_name>(&options) {
+                                panic!("{}", err);
+                            }
+                        }
                     }
-                    
-                    let mut file = File::create(&file_path)
-                        .expect("Failed to create documentation file");
-                        
-                    file.write_all(docs.as_bytes())
-                        .expect("Failed to write documentation");
-                    
-                    println!("Generated documentation: {}", file_path.display());
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // Register root types with the registry, if it's enabled. Exported structs
+    // are roots, and `#[config_docs(root)]` marks others.
+    let is_root = should_export || inject_path.is_some() || attrs.root;
+    let registered_export = if should_export || inject_path.is_some() {
+        quote! { Some(#export_settings) }
+    } else {
+        quote! { None }
+    };
+    let register_tokens = if cfg!(feature = "registry") && is_root {
+        quote! {
+            serde_config_docs::inventory::submit! {
+                serde_config_docs::RegisteredConfig {
+                    name: #struct_name_str,
+                    module_path: module_path!(),
+                    schema: <#struct_name as serde_config_docs::ConfigDocsStruct>::schema,
+                    manifest_dir: env!("CARGO_MANIFEST_DIR"),
+                    export: #registered_export,
                 }
             }
         }
@@ -115,28 +191,46 @@ pub fn derive_config_docs(input: TokenStream) -> TokenStream {
     let output = quote! {
         #trait_impl
         
+        #register_tokens
+
         #test_fn
     };
 
-    output.into()
+    Ok(output)
 }
 
 // Simplify process_fields to not need format at compile time
 fn process_fields(
     fields: &syn::punctuated::Punctuated<Field, syn::token::Comma>,
     rename_all: &Option<String>,
-) -> proc_macro2::TokenStream {
+    struct_name: &syn::Ident,
+    struct_default: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
     let field_tokens = fields.iter().map(|field| {
         // Get field name
         let field_name = field.ident.as_ref().unwrap();
         let field_name_str = field_name.to_string();
 
         // Extract doc comments
-        let doc_comment = extract_doc_comment(&field.attrs);
+        let doc_tokens = match extract_doc_comment(&field.attrs) {
+            Some(doc) => quote! { .doc(#doc) },
+            None => quote! {},
+        };
 
         // Extract serde attributes
         let rename = extract_rename(&field.attrs);
-        let default_fn = extract_default_fn(&field.attrs);
+        let default = extract_default(&field.attrs);
+
+        // Extract our own attributes
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+        let has_example = attrs.example.is_some();
+        let example_tokens = match &attrs.example {
+            Some(path) => {
+                let example_fn_path = function_path(path, "example")?;
+                quote! { .example_from(&#example_fn_path()) }
+            }
+            None => quote! {},
+        };
 
         // Determine final field name after rename attributes
         let final_name = match rename {
@@ -146,56 +240,79 @@ fn process_fields(
 
         // Get field type info
         let field_type_str = get_field_type_str(&field.ty);
-        let is_nested = is_nested_type(&field.ty);
+        let is_nested = is_nested_type(&field.ty) && !attrs.leaf;
 
         if is_nested {
             // For nested fields, we need to recursively process them
             let nested_type_name = get_type_name(&field.ty);
             let nested_type_ident = format_ident!("{}", nested_type_name);
 
-            quote! {
+            Ok(quote! {
                 .add_field(
                     serde_config_docs::FieldInfo::new(#final_name)
-                        // .doc(#doc_comment)
+                        #doc_tokens
                         .field_type(#field_type_str)
                         .nested(<#nested_type_ident as serde_config_docs::ConfigDocsStruct>::schema().fields)
                 )
-            }
+            })
         } else {
-            let default_value_expr = match default_fn {
-                Some(path) => {
+            let ty = &field.ty;
+            let default_value_expr = match default {
+                Some(SerdeDefault::Path(path)) => {
                     // Create an expression to call the default function
-                    let default_fn_path = syn::parse_str::<ExprPath>(&path).unwrap_or_else(|_| {
-                        panic!("Failed to parse default function path: {}", path)
-                    });
-                    
-                    quote! {
-                        Some({
-                            // Get the default value and convert to a string
-                            let default_value = #default_fn_path();
-                            format!("{:?}", default_value)
-                        })
-                    }
-                },
-                None => {
-                    quote! { None }
+                    let default_fn_path = function_path(&path, "default")?;
+
+                    Some(quote! { #default_fn_path() })
                 }
+                Some(SerdeDefault::Trait) => {
+                    Some(quote! { <#ty as ::core::default::Default>::default() })
+                }
+                None if struct_default => Some(quote! {
+                    <#struct_name as ::core::default::Default>::default().#field_name
+                }),
+                None => None,
+            };
+
+            // Missing `Option` fields deserialize as `None`, so only other
+            // fields without a default have to be present in the config
+            let is_required = default_value_expr.is_none() && !is_option_type(ty);
+
+            // Examples hold a placeholder for required fields, which has to
+            // deserialize, so other types need an example value
+            if is_required && !has_example && !has_placeholder(ty) {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    format!(
+                        "required field `{}` needs an example value, as there's no placeholder \
+                         for its type. Add `#[config_docs(example = \"...\")]` naming a \
+                         function that returns one, or give it a serde default",
+                        field_name_str
+                    ),
+                ));
+            }
+
+            let default_tokens = match default_value_expr {
+                Some(expr) => quote! { .default_from(&#expr) },
+                None => quote! {},
             };
 
-            quote! {
+            Ok(quote! {
                 .add_field(
                     serde_config_docs::FieldInfo::new(#final_name)
-                        // .doc(#doc_comment)
-                        .default(#default_value_expr)
+                        #doc_tokens
+                        #default_tokens
+                        #example_tokens
                         .field_type(#field_type_str)
+                        .required(#is_required)
                 )
-            }
+            })
         }
     });
+    let field_tokens = field_tokens.collect::<syn::Result<Vec<_>>>()?;
 
-    quote! {
+    Ok(quote! {
         #(#field_tokens)*
-    }
+    })
 }
 
 fn extract_doc_comment(attrs: &[Attribute]) -> Option<String> {
@@ -208,7 +325,8 @@ fn extract_doc_comment(attrs: &[Attribute]) -> Option<String> {
                 ..
             })) = attr.parse_meta()
             {
-                doc_lines.push(lit_str.value());
+                let line = lit_str.value();
+                doc_lines.push(line.strip_prefix(' ').unwrap_or(&line).to_string());
             }
         }
     }
@@ -221,18 +339,32 @@ fn extract_doc_comment(attrs: &[Attribute]) -> Option<String> {
 }
 
 
-// Extract the default function path from serde attributes
-fn extract_default_fn(attrs: &[Attribute]) -> Option<String> {
+/// The ways serde can fill in a missing field
+enum SerdeDefault {
+    /// `#[serde(default)]`, which uses the type's `Default` impl
+    Trait,
+    /// `#[serde(default = "path")]`, which calls the given function
+    Path(LitStr),
+}
+
+// Extract the default behaviour from serde attributes
+fn extract_default(attrs: &[Attribute]) -> Option<SerdeDefault> {
     for attr in attrs {
         if attr.path.is_ident("serde") {
             if let Ok(Meta::List(meta_list)) = attr.parse_meta() {
                 for nested in meta_list.nested {
-                    if let NestedMeta::Meta(Meta::NameValue(name_value)) = nested {
-                        if name_value.path.is_ident("default") {
+                    match nested {
+                        NestedMeta::Meta(Meta::NameValue(name_value))
+                            if name_value.path.is_ident("default") =>
+                        {
                             if let Lit::Str(lit_str) = name_value.lit {
-                                return Some(lit_str.value());
+                                return Some(SerdeDefault::Path(lit_str));
                             }
                         }
+                        NestedMeta::Meta(Meta::Path(path)) if path.is_ident("default") => {
+                            return Some(SerdeDefault::Trait);
+                        }
+                        _ => {}
                     }
                 }
             }
@@ -241,7 +373,261 @@ fn extract_default_fn(attrs: &[Attribute]) -> Option<String> {
     None
 }
 
+/// One item of a `config_docs(...)` attribute
+///
+/// These can't all be read as a `Meta`, since `formats = ["toml", "yaml"]`
+/// isn't a literal.
+enum ConfigDocsItem {
+    /// A bare flag, e.g. `leaf`
+    Flag(Ident),
+    /// A literal value, e.g. `env_prefix = "APP"`
+    Value(Ident, Lit),
+    /// A list of strings, e.g. `formats = ["toml", "yaml"]`
+    List(Ident, Vec<LitStr>),
+    /// Nested items, e.g. `export(check)`
+    Nested(Ident, Vec<ConfigDocsItem>),
+}
+
+impl Parse for ConfigDocsItem {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+
+        if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            if input.peek(token::Bracket) {
+                let content;
+                bracketed!(content in input);
+                let items = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                Ok(ConfigDocsItem::List(name, items.into_iter().collect()))
+            } else {
+                Ok(ConfigDocsItem::Value(name, input.parse()?))
+            }
+        } else if input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let items = Punctuated::<ConfigDocsItem, Token![,]>::parse_terminated(&content)?;
+            Ok(ConfigDocsItem::Nested(name, items.into_iter().collect()))
+        } else {
+            Ok(ConfigDocsItem::Flag(name))
+        }
+    }
+}
+
+impl ConfigDocsItem {
+    /// The name the item starts with
+    fn name(&self) -> &Ident {
+        match self {
+            ConfigDocsItem::Flag(name)
+            | ConfigDocsItem::Value(name, _)
+            | ConfigDocsItem::List(name, _)
+            | ConfigDocsItem::Nested(name, _) => name,
+        }
+    }
 
+    /// Expect a bare flag, e.g. `leaf`
+    fn flag(self) -> syn::Result<()> {
+        match self {
+            ConfigDocsItem::Flag(_) => Ok(()),
+            item => Err(syn::Error::new(
+                item.name().span(),
+                format!("`{}` doesn't take a value", item.name()),
+            )),
+        }
+    }
+
+    /// Expect a string value, e.g. `env_prefix = "APP"`
+    fn string(self) -> syn::Result<LitStr> {
+        match self {
+            ConfigDocsItem::Value(_, Lit::Str(value)) => Ok(value),
+            ConfigDocsItem::Value(name, value) => Err(syn::Error::new_spanned(
+                value,
+                format!("expected a string, as in `{} = \"...\"`", name),
+            )),
+            item => Err(syn::Error::new(
+                item.name().span(),
+                format!("expected a string, as in `{} = \"...\"`", item.name()),
+            )),
+        }
+    }
+
+    /// Expect a list of strings, e.g. `formats = ["toml", "yaml"]`
+    fn list(self) -> syn::Result<Vec<LitStr>> {
+        match self {
+            ConfigDocsItem::List(_, items) => Ok(items),
+            item => Err(syn::Error::new(
+                item.name().span(),
+                format!("expected a list of strings, as in `{} = [\"...\"]`", item.name()),
+            )),
+        }
+    }
+}
+
+// Collect the items of every `#[config_docs(...)]` attribute
+fn config_docs_items(attrs: &[Attribute]) -> syn::Result<Vec<ConfigDocsItem>> {
+    let mut items = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("config_docs")) {
+        items.extend(
+            attr.parse_args_with(Punctuated::<ConfigDocsItem, Token![,]>::parse_terminated)?,
+        );
+    }
+    Ok(items)
+}
+
+/// The error for an option `config_docs` doesn't have where it's used
+fn unknown_option(name: &Ident, expected: &[&str]) -> syn::Error {
+    let expected: Vec<String> = expected.iter().map(|option| format!("`{}`", option)).collect();
+    syn::Error::new(
+        name.span(),
+        format!(
+            "unknown `config_docs` option `{}`, expected one of {}",
+            name,
+            expected.join(", ")
+        ),
+    )
+}
+
+/// Parse a function path given as a string, e.g. `example = "default_port"`
+fn function_path(path: &LitStr, option: &str) -> syn::Result<ExprPath> {
+    path.parse().map_err(|_| {
+        syn::Error::new(
+            path.span(),
+            format!("`{}` in `{}` isn't a function path", path.value(), option),
+        )
+    })
+}
+
+/// An optional string as `Some("...")` or `None`
+fn option_tokens(value: &Option<String>) -> proc_macro2::TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
+
+/// The `config_docs` attributes of the struct itself
+#[derive(Default)]
+struct StructAttrs {
+    /// Write the docs to files, as set by `export` or `export(...)`
+    export: Option<ExportAttr>,
+    /// The prefix of environment variable names
+    env_prefix: Option<String>,
+    /// A Markdown file to keep the docs up to date in, relative to the crate
+    inject: Option<String>,
+    /// Register the struct as a root type without exporting it
+    root: bool,
+}
+
+impl StructAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut parsed = StructAttrs::default();
+        for item in config_docs_items(attrs)? {
+            match item.name().to_string().as_str() {
+                "export" => parsed.export = Some(ExportAttr::parse(item)?),
+                "env_prefix" => parsed.env_prefix = Some(item.string()?.value()),
+                "inject" => parsed.inject = Some(item.string()?.value()),
+                "root" => {
+                    item.flag()?;
+                    parsed.root = true;
+                }
+                _ => {
+                    return Err(unknown_option(
+                        item.name(),
+                        &["export", "env_prefix", "inject", "root"],
+                    ))
+                }
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// The `config_docs` attributes of a field
+#[derive(Default)]
+struct FieldAttrs {
+    /// Document the field as a single value, even if its type is a struct
+    leaf: bool,
+    /// A function returning an example value
+    example: Option<LitStr>,
+}
+
+impl FieldAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut parsed = FieldAttrs::default();
+        for item in config_docs_items(attrs)? {
+            match item.name().to_string().as_str() {
+                "leaf" => {
+                    item.flag()?;
+                    parsed.leaf = true;
+                }
+                "example" => parsed.example = Some(item.string()?),
+                _ => return Err(unknown_option(item.name(), &["leaf", "example"])),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// The options of the struct-level `export` attribute
+#[derive(Default)]
+struct ExportAttr {
+    /// Compare the docs on disk against the generated ones instead of
+    /// writing them
+    check: bool,
+    /// Also write the docs split into one page per section
+    pages: bool,
+    /// Where to write the docs, relative to the crate
+    path: Option<String>,
+    /// The names of the formats to export
+    formats: Vec<String>,
+    /// The title of the generated document
+    title: Option<String>,
+    /// Other root types to document after this one, in the same document
+    combine: Vec<LitStr>,
+}
+
+impl ExportAttr {
+    /// Parse `export`, or `export(...)` with options like
+    /// `export(check, path = "book/src/config.md", formats = ["toml", "yaml"])`
+    fn parse(item: ConfigDocsItem) -> syn::Result<Self> {
+        let mut export = ExportAttr::default();
+        let options = match item {
+            ConfigDocsItem::Flag(_) => return Ok(export),
+            ConfigDocsItem::Nested(_, options) => options,
+            item => {
+                return Err(syn::Error::new(
+                    item.name().span(),
+                    "expected `export` or `export(...)`",
+                ))
+            }
+        };
+
+        for option in options {
+            match option.name().to_string().as_str() {
+                "check" => {
+                    option.flag()?;
+                    export.check = true;
+                }
+                "pages" => {
+                    option.flag()?;
+                    export.pages = true;
+                }
+                "path" => export.path = Some(option.string()?.value()),
+                "title" => export.title = Some(option.string()?.value()),
+                "formats" => {
+                    export.formats = option.list()?.iter().map(LitStr::value).collect();
+                }
+                "combine" => export.combine = option.list()?,
+                _ => {
+                    return Err(unknown_option(
+                        option.name(),
+                        &["check", "pages", "path", "title", "formats", "combine"],
+                    ))
+                }
+            }
+        }
+        Ok(export)
+    }
+}
 
 fn extract_rename(attrs: &[Attribute]) -> Option<String> {
     for attr in attrs {
@@ -307,17 +693,88 @@ fn is_nested_type(ty: &Type) -> bool {
                     | "char"
                     | "str"
                     | "String"
+                    // Standard containers and leaf types are not nested either
+                    | "Option"
+                    | "Vec"
+                    | "VecDeque"
+                    | "HashMap"
+                    | "BTreeMap"
+                    | "HashSet"
+                    | "BTreeSet"
+                    | "PathBuf"
+                    | "OsString"
+                    | "Duration"
+                    | "IpAddr"
+                    | "Ipv4Addr"
+                    | "Ipv6Addr"
+                    | "SocketAddr"
+                    | "SocketAddrV4"
+                    | "SocketAddrV6"
             )
         }
         _ => false,
     }
 }
 
+// Check whether examples have a placeholder value for a leaf type, as
+// `placeholder_value` in serde-config-docs writes them
+fn has_placeholder(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    type_path.path.segments.last().is_some_and(|segment| {
+        matches!(
+            segment.ident.to_string().as_str(),
+            "bool"
+                | "i8"
+                | "i16"
+                | "i32"
+                | "i64"
+                | "i128"
+                | "isize"
+                | "u8"
+                | "u16"
+                | "u32"
+                | "u64"
+                | "u128"
+                | "usize"
+                | "f32"
+                | "f64"
+                | "char"
+                | "String"
+                | "PathBuf"
+                | "OsString"
+                | "IpAddr"
+                | "Ipv4Addr"
+                | "Ipv6Addr"
+                | "SocketAddr"
+                | "SocketAddrV4"
+                | "SocketAddrV6"
+                | "Duration"
+                | "Vec"
+                | "VecDeque"
+                | "HashMap"
+                | "BTreeMap"
+                | "HashSet"
+                | "BTreeSet"
+        )
+    })
+}
+
+fn is_option_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
 fn get_field_type_str(ty: &Type) -> String {
     match ty {
-        Type::Path(type_path) if type_path.path.segments.len() == 1 => {
-            type_path.path.segments[0].ident.to_string()
-        }
+        Type::Path(_) => quote!(#ty).to_string().replace(' ', ""),
         _ => "unknown".to_string(),
     }
 }
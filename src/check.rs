@@ -0,0 +1,190 @@
+// serde_config_docs/src/check.rs
+//! Catch committed docs that have gone stale
+//!
+//! In check mode, generated docs are compared against the files on disk
+//! instead of overwriting them, so CI fails when someone forgets to
+//! regenerate them.
+
+use crate::ShapeError;
+
+use similar::TextDiff;
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Whether generated docs are written to disk or checked against it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DocsMode {
+    /// Overwrite the files with the generated docs
+    #[default]
+    Write,
+    /// Leave the files alone and fail if they differ from the generated docs
+    Check,
+}
+
+impl DocsMode {
+    /// Check docs if `CONFIG_DOCS_CHECK` is set to anything but an empty
+    /// string or `0`, and write them otherwise
+    pub fn from_env() -> Self {
+        Self::from_env_value(env::var("CONFIG_DOCS_CHECK").ok().as_deref())
+    }
+
+    fn from_env_value(value: Option<&str>) -> Self {
+        match value {
+            Some(value) if !value.is_empty() && value != "0" => DocsMode::Check,
+            _ => DocsMode::Write,
+        }
+    }
+}
+
+/// Write generated docs to `path`, or in check mode make sure the file
+/// already holds exactly them
+pub fn update_docs_file(
+    path: impl AsRef<Path>,
+    contents: &str,
+    mode: DocsMode,
+) -> Result<(), DocsError> {
+    let path = path.as_ref();
+    let io_error = |error| DocsError::Io {
+        path: path.to_path_buf(),
+        error,
+    };
+
+    match mode {
+        DocsMode::Write => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(io_error)?;
+            }
+            fs::write(path, contents).map_err(io_error)
+        }
+        DocsMode::Check => {
+            let current = match fs::read_to_string(path) {
+                Ok(current) => current,
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                    return Err(DocsError::Missing {
+                        path: path.to_path_buf(),
+                    });
+                }
+                Err(error) => return Err(io_error(error)),
+            };
+
+            if current == contents {
+                return Ok(());
+            }
+
+            let name = path.display().to_string();
+            let diff = TextDiff::from_lines(current.as_str(), contents)
+                .unified_diff()
+                .header(&name, &name)
+                .to_string();
+            Err(DocsError::Stale {
+                path: path.to_path_buf(),
+                diff,
+            })
+        }
+    }
+}
+
+/// Error returned when generated docs can't be written, or don't match the
+/// committed ones in check mode
+#[derive(Debug)]
+pub enum DocsError {
+    /// The file differs from the generated docs
+    Stale {
+        path: PathBuf,
+        /// A unified diff from the file's contents to the generated docs
+        diff: String,
+    },
+    /// The file doesn't exist yet
+    Missing { path: PathBuf },
+    /// The file couldn't be read or written
+    Io { path: PathBuf, error: io::Error },
+    /// The docs can't be written in the chosen format
+    Shape(ShapeError),
+}
+
+impl fmt::Display for DocsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocsError::Stale { path, diff } => {
+                writeln!(
+                    f,
+                    "{} is out of date with the generated docs:",
+                    path.display()
+                )?;
+                writeln!(f)?;
+                write!(f, "{}", diff)
+            }
+            DocsError::Missing { path } => {
+                write!(f, "{} hasn't been generated yet", path.display())
+            }
+            DocsError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            DocsError::Shape(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for DocsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DocsError::Io { error, .. } => Some(error),
+            DocsError::Shape(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mode_from_env_value() {
+        assert_eq!(DocsMode::from_env_value(None), DocsMode::Write);
+        assert_eq!(DocsMode::from_env_value(Some("")), DocsMode::Write);
+        assert_eq!(DocsMode::from_env_value(Some("0")), DocsMode::Write);
+        assert_eq!(DocsMode::from_env_value(Some("1")), DocsMode::Check);
+        assert_eq!(DocsMode::from_env_value(Some("true")), DocsMode::Check);
+    }
+
+    #[test]
+    fn write_creates_the_file_and_its_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("docs/Config.md");
+
+        update_docs_file(&path, "docs\n", DocsMode::Write).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "docs\n");
+        update_docs_file(&path, "docs\n", DocsMode::Check).unwrap();
+    }
+
+    #[test]
+    fn check_reports_a_stale_file_and_leaves_it_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Config.md");
+        fs::write(&path, "# Config\nold\n").unwrap();
+
+        let err = update_docs_file(&path, "# Config\nnew\n", DocsMode::Check).unwrap_err();
+        match err {
+            DocsError::Stale { path: stale, diff } => {
+                assert_eq!(stale, path);
+                assert!(diff.contains("-old\n"), "{}", diff);
+                assert!(diff.contains("+new\n"), "{}", diff);
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "# Config\nold\n");
+    }
+
+    #[test]
+    fn check_reports_a_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Config.md");
+
+        let err = update_docs_file(&path, "docs", DocsMode::Check).unwrap_err();
+        assert!(matches!(err, DocsError::Missing { path: missing } if missing == path));
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,535 @@
+// serde_config_docs/src/dotenv.rs
+//! `.env` files, as read by docker-compose and most twelve-factor apps
+//!
+//! Every leaf field becomes one variable named after its path in the schema,
+//! e.g. `APP_SERVER__PORT=8080`, and each section starts with a comment
+//! banner. Values are written the way the flat formats write them, so lists
+//! are comma-separated and maps can't be expressed at all.
+
+use crate::flat_value::flat_text;
+use crate::render::{
+    join_path, resolve_key, write_annotation, write_comment_lines, RenderContext, Renderer,
+};
+use crate::{
+    env_var_name, ConfigFormat, ConfigSchema, DefaultsStyle, FieldInfo, ShapeError, Value,
+};
+
+use std::fmt::{self, Write};
+
+#[derive(Debug)]
+pub(crate) struct Dotenv;
+
+impl Renderer for Dotenv {
+    fn name(&self) -> &str {
+        "env"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["dotenv"]
+    }
+
+    fn extension(&self) -> &str {
+        "env"
+    }
+
+    fn code_fence(&self) -> &str {
+        "dotenv"
+    }
+
+    fn comment_marker(&self) -> Option<&str> {
+        Some("#")
+    }
+
+    fn render_value(&self, value: &Value) -> String {
+        render_value(value)
+    }
+
+    fn render_document(&self, value: &Value) -> Result<String, ShapeError> {
+        format_value(value)
+    }
+
+    fn validate(&self, fields: &[FieldInfo]) -> Result<(), ShapeError> {
+        validate(fields, "")
+    }
+
+    fn write_section(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        section: &FieldInfo,
+        path: &[&str],
+    ) -> fmt::Result {
+        write_variables(
+            buffer,
+            ctx.env_prefix,
+            &section.nested_fields,
+            path,
+            ctx.defaults,
+        )
+    }
+
+    fn write_document(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        fields: &[FieldInfo],
+    ) -> fmt::Result {
+        write_section(buffer, ctx.env_prefix, fields, &[], ctx.defaults)
+    }
+
+    fn parse(&self, input: &str, schema: Option<&ConfigSchema>) -> Result<Value, String> {
+        parse(input, schema)
+    }
+}
+
+/// Check that every value below these fields fits in a single variable
+fn validate(fields: &[FieldInfo], path: &str) -> Result<(), ShapeError> {
+    for field in fields {
+        let current_path = join_path(path, &field.name);
+
+        if field.is_nested {
+            validate(&field.nested_fields, &current_path)?;
+            continue;
+        }
+
+        for value in [&field.default_value, &field.example_value]
+            .into_iter()
+            .flatten()
+            .filter(|value| !value.is_null())
+        {
+            if let Err(reason) = flat_text(value) {
+                return Err(ShapeError {
+                    format: ConfigFormat::Dotenv,
+                    path: current_path,
+                    reason: reason.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Write the variables of a section, followed by its nested sections
+fn write_section(
+    buffer: &mut String,
+    env_prefix: Option<&str>,
+    fields: &[FieldInfo],
+    path: &[&str],
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    write_variables(buffer, env_prefix, fields, path, defaults)?;
+
+    for field in fields.iter().filter(|f| f.is_nested) {
+        let mut current_path = path.to_vec();
+        current_path.push(&field.name);
+
+        write_banner(buffer, field, &current_path)?;
+        write_section(
+            buffer,
+            env_prefix,
+            &field.nested_fields,
+            &current_path,
+            defaults,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Write a comment banner introducing a section
+fn write_banner(buffer: &mut String, field: &FieldInfo, path: &[&str]) -> fmt::Result {
+    let rule = "#".repeat(40);
+
+    writeln!(buffer, "{}", rule)?;
+    writeln!(buffer, "# {}", path.join("."))?;
+    if field.doc_comments.is_some() {
+        writeln!(buffer, "#")?;
+        write_comment_lines(buffer, field.doc_comments.as_deref(), "#")?;
+    }
+    writeln!(buffer, "{}", rule)?;
+    writeln!(buffer)
+}
+
+/// Write a documented variable for each leaf field of the section at `path`
+fn write_variables(
+    buffer: &mut String,
+    env_prefix: Option<&str>,
+    fields: &[FieldInfo],
+    path: &[&str],
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    for field in fields.iter().filter(|f| !f.is_nested) {
+        write_comment_lines(buffer, field.doc_comments.as_deref(), "#")?;
+
+        let key = resolve_key(field, defaults);
+        write_annotation(buffer, key.annotation.as_ref(), "#", &Dotenv)?;
+
+        let mut field_path = path.to_vec();
+        field_path.push(&field.name);
+        let name = env_var_name(env_prefix, &field_path);
+
+        let text = flat_text(&key.value).unwrap_or_else(|reason| {
+            panic!(
+                "`{}` can't be written as env: {}",
+                field_path.join("."),
+                reason
+            )
+        });
+        if key.commented {
+            writeln!(buffer, "# {}={}", name, quote(&text))?;
+        } else {
+            writeln!(buffer, "{}={}", name, quote(&text))?;
+        }
+        writeln!(buffer)?;
+    }
+
+    Ok(())
+}
+
+/// Render a value on a single line, as written after a variable name
+fn render_value(value: &Value) -> String {
+    match flat_text(value) {
+        // An empty value is nothing after the `=`, which an annotation or a
+        // table cell wouldn't show
+        Ok(text) if text.is_empty() => "''".to_string(),
+        Ok(text) => quote(&text),
+        // Maps are rejected by `validate` before they're written, but can
+        // still be shown as the variables they'd need, e.g. `KA=v, KB=w`
+        Err(_) => {
+            let lines = format_value(&value.externally_tagged()).unwrap_or_default();
+            let variables: Vec<&str> = lines.lines().collect();
+            if variables.is_empty() {
+                "''".to_string()
+            } else {
+                variables.join(", ")
+            }
+        }
+    }
+}
+
+/// Render a whole value as one variable per leaf, without a prefix
+fn format_value(value: &Value) -> Result<String, ShapeError> {
+    fn walk(buffer: &mut String, path: &mut Vec<String>, value: &Value) -> Result<(), ShapeError> {
+        match value {
+            Value::Table(entries) | Value::Struct(_, entries) if !entries.is_empty() => {
+                for (key, value) in entries {
+                    path.push(key.clone());
+                    walk(buffer, path, value)?;
+                    path.pop();
+                }
+            }
+            Value::Null => {}
+            value => {
+                let text = flat_text(value).map_err(|reason| ShapeError {
+                    format: ConfigFormat::Dotenv,
+                    path: path.join("."),
+                    reason: reason.to_string(),
+                })?;
+                let path: Vec<&str> = path.iter().map(String::as_str).collect();
+                writeln!(buffer, "{}={}", env_var_name(None, &path), quote(&text)).unwrap();
+            }
+        }
+        Ok(())
+    }
+
+    let mut buffer = String::new();
+    walk(&mut buffer, &mut Vec::new(), value)?;
+    Ok(buffer)
+}
+
+/// Quote a value if a shell or docker-compose would otherwise change it
+///
+/// Single quotes are taken literally, so they're preferred whenever the value
+/// fits in them. Double quotes are used for line breaks and single quotes.
+/// Unlike the other formats' strings, `$` and backticks have to be escaped,
+/// since shells expand them inside double quotes.
+fn quote(text: &str) -> String {
+    let is_plain = text
+        .chars()
+        .all(|c| c.is_alphanumeric() || "-_.,:/@+%".contains(c));
+    if is_plain {
+        return text.to_string();
+    }
+
+    if !text.contains(['\'', '\n', '\r']) {
+        return format!("'{}'", text);
+    }
+
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '$' => quoted.push_str("\\$"),
+            '`' => quoted.push_str("\\`"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Parse an env file into a tree of tables, with all values as strings
+///
+/// Variable names can't be mapped back to keys on their own, since renamed
+/// keys lose their case, so this needs the schema the file was written for.
+fn parse(input: &str, schema: Option<&ConfigSchema>) -> Result<Value, String> {
+    let Some(schema) = schema else {
+        return Err("reading env files needs a schema, see `ConfigSchema::parse`".to_string());
+    };
+
+    let mut variables = Vec::new();
+    for (number, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else {
+            return Err(format!(
+                "line {}: expected `NAME=value`, found `{}`",
+                number + 1,
+                line
+            ));
+        };
+        variables.push((name.trim().to_string(), unquote(value.trim())));
+    }
+
+    let table = build_table(
+        schema.env_prefix.as_deref(),
+        &schema.fields,
+        &[],
+        &mut variables,
+    );
+
+    match variables.first() {
+        Some((name, _)) => Err(format!("`{}` doesn't match any key", name)),
+        None => Ok(table),
+    }
+}
+
+/// Build the table for a section, taking its variables out of `variables`
+fn build_table(
+    env_prefix: Option<&str>,
+    fields: &[FieldInfo],
+    path: &[&str],
+    variables: &mut Vec<(String, String)>,
+) -> Value {
+    let mut entries = Vec::new();
+
+    for field in fields {
+        let mut field_path = path.to_vec();
+        field_path.push(&field.name);
+
+        if field.is_nested {
+            let table = build_table(env_prefix, &field.nested_fields, &field_path, variables);
+            entries.push((field.name.clone(), table));
+            continue;
+        }
+
+        let name = env_var_name(env_prefix, &field_path);
+        if let Some(i) = variables.iter().position(|(n, _)| *n == name) {
+            let (_, value) = variables.remove(i);
+            entries.push((field.name.clone(), Value::String(value)));
+        }
+    }
+
+    Value::Table(entries)
+}
+
+/// Undo the quoting of a value, dropping a trailing comment after plain ones
+fn unquote(value: &str) -> String {
+    if let Some(literal) = value.strip_prefix('\'') {
+        return literal.split('\'').next().unwrap_or_default().to_string();
+    }
+
+    let Some(quoted) = value.strip_prefix('"') else {
+        return match value.find(" #") {
+            Some(i) => value[..i].trim_end().to_string(),
+            None => value.to_string(),
+        };
+    };
+
+    let mut unquoted = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => match chars.next() {
+                Some('n') => unquoted.push('\n'),
+                Some('r') => unquoted.push('\r'),
+                Some('t') => unquoted.push('\t'),
+                Some(c) => unquoted.push(c),
+                None => {}
+            },
+            c => unquoted.push(c),
+        }
+    }
+    unquoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExampleOptions;
+
+    use serde::Deserialize;
+
+    fn schema() -> ConfigSchema {
+        ConfigSchema::builder()
+            .name("Config")
+            .env_prefix("APP")
+            .add_field(
+                FieldInfo::new("port")
+                    .doc("Port")
+                    .field_type("u16")
+                    .default(Value::Integer(8080)),
+            )
+            .add_field(
+                FieldInfo::new("tags")
+                    .field_type("Vec<String>")
+                    .default(Value::Array(vec![Value::from("a"), Value::from("b c")])),
+            )
+            .add_field(FieldInfo::new("server").doc("Server settings").nested(vec![
+                FieldInfo::new("host")
+                    .field_type("String")
+                    .default(Value::from("localhost")),
+                FieldInfo::new("password")
+                    .field_type("String")
+                    .required(true),
+            ]))
+            .build()
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Config {
+        #[serde(default)]
+        port: u16,
+        #[serde(default)]
+        tags: Vec<String>,
+        server: Server,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Server {
+        #[serde(default)]
+        host: String,
+        password: String,
+    }
+
+    #[test]
+    fn variables_are_named_after_their_path() {
+        let schema = schema();
+        let example =
+            schema.generate_example_with_options(&ExampleOptions::new(ConfigFormat::Dotenv));
+        assert_eq!(
+            example,
+            "\
+# Port
+# Default: 8080
+APP_PORT=8080
+
+# Default: 'a,b c'
+APP_TAGS='a,b c'
+
+########################################
+# server
+#
+# Server settings
+########################################
+
+# Default: localhost
+APP_SERVER__HOST=localhost
+
+# Required
+APP_SERVER__PASSWORD=
+"
+        );
+
+        let config: Config = schema.parse(ConfigFormat::Dotenv, &example).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                port: 8080,
+                tags: vec!["a".to_string(), "b c".to_string()],
+                server: Server {
+                    host: "localhost".to_string(),
+                    password: String::new(),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn values_survive_quoting() {
+        let cases = [
+            ("localhost", "localhost"),
+            ("https://example.com/a,b", "https://example.com/a,b"),
+            ("two words", "'two words'"),
+            ("$HOME # not a comment", "'$HOME # not a comment'"),
+            ("it's `$HOME`", "\"it's \\`\\$HOME\\`\""),
+            ("line\nbreak \"quoted\"", "\"line\\nbreak \\\"quoted\\\"\""),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(quote(text), expected);
+            assert_eq!(unquote(expected), text);
+        }
+    }
+
+    #[test]
+    fn values_are_shown_in_env_syntax() {
+        assert_eq!(render_value(&Value::from("two words")), "'two words'");
+        assert_eq!(render_value(&Value::Array(Vec::new())), "''");
+        assert_eq!(render_value(&Value::Null), "''");
+
+        let labels = Value::Table(vec![
+            ("ka".to_string(), Value::from("v")),
+            ("kb".to_string(), Value::from("w")),
+        ]);
+        assert_eq!(render_value(&labels), "KA=v, KB=w");
+    }
+
+    #[test]
+    fn plain_values_drop_trailing_comments() {
+        let schema = schema();
+        let input = "export APP_SERVER__PASSWORD=secret # set me\nAPP_PORT = 80\n";
+        let config: Config = schema.parse(ConfigFormat::Dotenv, input).unwrap();
+        assert_eq!(config.server.password, "secret");
+        assert_eq!(config.port, 80);
+    }
+
+    #[test]
+    fn malformed_files_are_errors() {
+        let schema = schema();
+        let err = parse("# comment\nAPP_PORT\n", Some(&schema)).unwrap_err();
+        assert_eq!(err, "line 2: expected `NAME=value`, found `APP_PORT`");
+
+        let err = parse("APP_PROT=80\n", Some(&schema)).unwrap_err();
+        assert_eq!(err, "`APP_PROT` doesn't match any key");
+
+        assert!(parse("APP_PORT=80\n", None).is_err());
+    }
+
+    #[test]
+    fn maps_are_a_shape_error() {
+        let fields = [
+            FieldInfo::new("server").nested(vec![FieldInfo::new("labels")
+                .field_type("HashMap<String, String>")
+                .default(Value::Table(vec![("a".to_string(), Value::from("b"))]))]),
+        ];
+        let err = Dotenv.validate(&fields).unwrap_err();
+        assert_eq!(err.path, "server.labels");
+    }
+
+    #[test]
+    fn documents_with_nested_lists_are_a_shape_error() {
+        let server = Value::Table(vec![("host".to_string(), Value::from("a"))]);
+        let value = Value::Table(vec![("servers".to_string(), Value::Array(vec![server]))]);
+        let err = Dotenv.render_document(&value).unwrap_err();
+        assert_eq!(err.path, "servers");
+    }
+}
@@ -0,0 +1,166 @@
+// serde_config_docs/src/embed.rs
+//! Ship the docs for a binary's own schema inside the binary
+//!
+//! The docs can be generated the first time they're needed with
+//! [`config_docs!`](crate::config_docs) or
+//! [`try_config_docs!`](crate::try_config_docs), or by a build script with
+//! [`write_build_docs`] and then included with
+//! [`include_build_docs!`](crate::include_build_docs). Build scripts can only
+//! use types from their build dependencies, so the second way needs the
+//! config types to live in a crate of their own.
+
+use crate::{ConfigDocsStruct, MarkdownOptions};
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Generate docs for a config type once, and keep them for the rest of the
+/// program as a `&'static str`, e.g.
+/// `config_docs!(Config, MarkdownOptions::new(ConfigFormat::Toml))`
+///
+/// The options are only evaluated the first time the docs are generated.
+///
+/// # Panics
+///
+/// Panics if the type holds a value the format can't express. Use
+/// [`try_config_docs!`](crate::try_config_docs) to get a `ShapeError` instead.
+#[macro_export]
+macro_rules! config_docs {
+    ($ty:ty, $options:expr) => {
+        match $crate::try_config_docs!($ty, $options) {
+            ::std::result::Result::Ok(docs) => docs,
+            ::std::result::Result::Err(err) => ::std::panic!("{}", err),
+        }
+    };
+}
+
+/// Generate docs for a config type once, and keep them for the rest of the
+/// program as a `Result<&'static str, ShapeError>`
+///
+/// The error is kept as well, so it's only worked out once.
+#[macro_export]
+macro_rules! try_config_docs {
+    ($ty:ty, $options:expr) => {{
+        static DOCS: ::std::sync::OnceLock<
+            ::std::result::Result<::std::string::String, $crate::ShapeError>,
+        > = ::std::sync::OnceLock::new();
+        DOCS.get_or_init(|| $crate::try_generate_config_docs_with_options::<$ty>(&$options))
+            .as_deref()
+            .map_err(::std::clone::Clone::clone)
+    }};
+}
+
+/// Include docs written to `OUT_DIR` by [`write_build_docs`] as a
+/// `&'static str`, e.g. `include_build_docs!("config.md")`
+#[macro_export]
+macro_rules! include_build_docs {
+    ($file_name:expr) => {
+        include_str!(concat!(env!("OUT_DIR"), "/", $file_name))
+    };
+}
+
+/// Generate docs for a config type in a build script, and write them to
+/// `file_name` in `OUT_DIR` for [`include_build_docs!`](crate::include_build_docs)
+///
+/// The file is only rewritten when the docs change, and the path it was
+/// written to is returned. Docs that can't be written in the chosen format
+/// fail with [`io::ErrorKind::InvalidInput`].
+pub fn write_build_docs<T: ConfigDocsStruct>(
+    file_name: &str,
+    options: &MarkdownOptions,
+) -> io::Result<PathBuf> {
+    let out_dir = env::var_os("OUT_DIR").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "OUT_DIR isn't set, so this isn't running in a build script",
+        )
+    })?;
+    write_build_docs_to::<T>(Path::new(&out_dir), file_name, options)
+}
+
+/// Write the docs for [`write_build_docs`] to `file_name` in `out_dir`
+fn write_build_docs_to<T: ConfigDocsStruct>(
+    out_dir: &Path,
+    file_name: &str,
+    options: &MarkdownOptions,
+) -> io::Result<PathBuf> {
+    let path = out_dir.join(file_name);
+    let docs = T::schema()
+        .try_generate_docs_with_options(options)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+    if fs::read_to_string(&path).ok().as_deref() != Some(docs.as_str()) {
+        fs::write(&path, docs)?;
+    }
+    Ok(path)
+}
+
+#[cfg(all(test, feature = "toml"))]
+mod tests {
+    use super::*;
+    use crate::{ConfigFormat, ConfigSchema, FieldInfo};
+
+    struct Config;
+
+    impl ConfigDocsStruct for Config {
+        fn schema() -> ConfigSchema {
+            ConfigSchema::builder()
+                .name("Config")
+                .add_field(FieldInfo::new("port").field_type("u16"))
+                .build()
+        }
+    }
+
+    fn docs() -> &'static str {
+        crate::config_docs!(Config, MarkdownOptions::new(ConfigFormat::Toml))
+    }
+
+    #[test]
+    fn docs_are_generated_once() {
+        let first = docs();
+        assert!(first.contains("port = 0"), "{}", first);
+        assert!(std::ptr::eq(first, docs()));
+    }
+
+    #[cfg(feature = "ini")]
+    #[test]
+    fn try_config_docs_keeps_the_error() {
+        use crate::Value;
+
+        struct Cluster;
+
+        impl ConfigDocsStruct for Cluster {
+            fn schema() -> ConfigSchema {
+                let server = Value::Table(vec![("host".to_string(), Value::from("a"))]);
+                ConfigSchema::builder()
+                    .name("Cluster")
+                    .add_field(
+                        FieldInfo::new("servers")
+                            .field_type("Vec<Server>")
+                            .default(Value::Array(vec![server])),
+                    )
+                    .build()
+            }
+        }
+
+        let err =
+            crate::try_config_docs!(Cluster, MarkdownOptions::new(ConfigFormat::Ini)).unwrap_err();
+        assert_eq!(err.path, "servers");
+    }
+
+    #[test]
+    fn build_docs_are_written_to_out_dir() {
+        let options = MarkdownOptions::new(ConfigFormat::Toml);
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = write_build_docs_to::<Config>(dir.path(), "config.md", &options).unwrap();
+        assert_eq!(path, dir.path().join("config.md"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), docs());
+
+        fs::write(&path, "stale").unwrap();
+        write_build_docs_to::<Config>(dir.path(), "config.md", &options).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), docs());
+    }
+}
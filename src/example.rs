@@ -0,0 +1,463 @@
+// serde_config_docs/src/example.rs
+//! Generate complete, commented example config files
+//!
+//! Unlike the Markdown output, which shows one snippet per section, an
+//! example config is a single file that can be shipped as-is, e.g. as
+//! `config.example.toml`.
+
+use crate::render::RenderContext;
+use crate::{ConfigDocsStruct, ConfigFormat, DefaultsStyle, FieldInfo, Value};
+
+use serde::de::DeserializeOwned;
+use std::fmt;
+
+/// Options to customize a generated example config file
+#[derive(Debug, Clone)]
+pub struct ExampleOptions {
+    pub format: ConfigFormat,
+    pub defaults: DefaultsStyle,
+}
+
+impl ExampleOptions {
+    /// Set the configuration format to write the example in
+    pub fn new(format: ConfigFormat) -> Self {
+        ExampleOptions {
+            format,
+            defaults: DefaultsStyle::default(),
+        }
+    }
+    /// Set how keys with default values are written
+    pub fn defaults(mut self, defaults: DefaultsStyle) -> Self {
+        self.defaults = defaults;
+        self
+    }
+}
+
+/// Generate a commented example config file for a type that implements ConfigDocsStruct
+///
+/// # Panics
+///
+/// Panics if the type holds a value the format can't express, e.g. an array
+/// of tables in INI. Use [`try_generate_example_config`] to get a
+/// [`ShapeError`] instead.
+pub fn generate_example_config<T: ConfigDocsStruct>(format: ConfigFormat) -> String {
+    generate_example_config_with_options::<T>(&ExampleOptions::new(format))
+}
+
+/// Generate a commented example config file for a type that implements
+/// ConfigDocsStruct, or fail if the format can't express it
+pub fn try_generate_example_config<T: ConfigDocsStruct>(
+    format: ConfigFormat,
+) -> Result<String, ShapeError> {
+    try_generate_example_config_with_options::<T>(&ExampleOptions::new(format))
+}
+
+/// Generate a commented example config file with custom options for a type that implements ConfigDocsStruct
+///
+/// # Panics
+///
+/// Panics if the type holds a value the format can't express. Use
+/// [`try_generate_example_config_with_options`] to get a [`ShapeError`]
+/// instead.
+pub fn generate_example_config_with_options<T: ConfigDocsStruct>(
+    options: &ExampleOptions,
+) -> String {
+    T::schema().generate_example_with_options(options)
+}
+
+/// Generate a commented example config file with custom options for a type
+/// that implements ConfigDocsStruct, or fail if the format can't express it
+pub fn try_generate_example_config_with_options<T: ConfigDocsStruct>(
+    options: &ExampleOptions,
+) -> Result<String, ShapeError> {
+    T::schema().try_generate_example_with_options(options)
+}
+
+/// Check that the generated example config deserializes back into `T`
+///
+/// This catches docs that drifted from the real `Deserialize` impl, e.g. a
+/// renamed field or a default the derive couldn't see.
+pub fn verify_example_config<T: ConfigDocsStruct + DeserializeOwned>(
+    options: &ExampleOptions,
+) -> Result<T, VerifyError> {
+    let schema = T::schema();
+
+    let example = schema
+        .try_generate_example_with_options(options)
+        .map_err(|err| VerifyError {
+            type_name: std::any::type_name::<T>(),
+            format: options.format,
+            message: err.to_string(),
+            example: String::new(),
+        })?;
+
+    options
+        .format
+        .parse_with_schema(&example, Some(&schema))
+        .map_err(|message| VerifyError {
+            type_name: std::any::type_name::<T>(),
+            format: options.format,
+            message,
+            example,
+        })
+}
+
+/// Error returned when a generated example config doesn't deserialize
+#[derive(Debug, Clone)]
+pub struct VerifyError {
+    /// The type the example was generated for
+    pub type_name: &'static str,
+    pub format: ConfigFormat,
+    /// The error reported by the format's deserializer
+    pub message: String,
+    /// The generated example that failed to deserialize
+    pub example: String,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "the generated {} example for `{}` does not deserialize: {}",
+            self.format.extension(),
+            self.type_name,
+            self.message.trim_end()
+        )?;
+        writeln!(f)?;
+        for (number, line) in self.example.lines().enumerate() {
+            writeln!(f, "{:>4} | {}", number + 1, line)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Error returned when a schema holds a value the format can't express, e.g.
+/// an array of tables in INI
+#[derive(Debug, Clone)]
+pub struct ShapeError {
+    pub format: ConfigFormat,
+    /// The dotted path of the offending key, or empty if the whole value
+    /// can't be written
+    pub path: String,
+    /// Why the value can't be written
+    pub reason: String,
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "the value")?;
+        } else {
+            write!(f, "`{}`", self.path)?;
+        }
+        write!(
+            f,
+            " can't be written as {}: {}",
+            self.format.extension(),
+            self.reason
+        )
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+/// Generate a commented example config file for a list of fields
+///
+/// # Panics
+///
+/// Panics if the fields hold a value the format can't express. Use
+/// [`try_generate_example`] to get a [`ShapeError`] instead.
+pub fn generate_example(fields: &[FieldInfo], options: &ExampleOptions) -> String {
+    try_generate_example(fields, options).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Generate a commented example config file for a list of fields, or fail if
+/// the format can't express them, e.g. an array of tables in INI
+pub fn try_generate_example(
+    fields: &[FieldInfo],
+    options: &ExampleOptions,
+) -> Result<String, ShapeError> {
+    generate_named_example(None, None, fields, options)
+}
+
+/// Generate an example config file for the fields of a type with the given
+/// name, for formats that write the root type's name
+pub(crate) fn generate_named_example(
+    name: Option<&str>,
+    env_prefix: Option<&str>,
+    fields: &[FieldInfo],
+    options: &ExampleOptions,
+) -> Result<String, ShapeError> {
+    let renderer = options.format.renderer();
+    renderer.validate(fields)?;
+
+    let ctx = RenderContext {
+        name,
+        env_prefix,
+        defaults: options.defaults,
+    };
+
+    let mut buffer = String::new();
+    renderer.write_document(&mut buffer, &ctx, fields).unwrap();
+
+    // Every block ends in a blank separator line, which the file doesn't need
+    let len = buffer.trim_end().len();
+    buffer.truncate(len);
+    buffer.push('\n');
+    Ok(buffer)
+}
+
+/// A stand-in value of the right shape for a field without a default
+///
+/// Types without a placeholder of their own get an empty string. The derive
+/// macro asks for an `example` on required fields of those types instead,
+/// since an empty string rarely deserializes into them.
+pub(crate) fn placeholder_value(field_type: &str) -> Value {
+    let field_type = field_type
+        .strip_prefix("Option<")
+        .and_then(|inner| inner.strip_suffix('>'))
+        .unwrap_or(field_type);
+
+    // Compare the last path segment without generics, e.g. `Vec` in `std::vec::Vec<u8>`
+    let base = field_type.split('<').next().unwrap_or(field_type);
+    let base = base.rsplit("::").next().unwrap_or(base);
+
+    match base {
+        "bool" => Value::Bool(false),
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => Value::Integer(0),
+        "f32" | "f64" => Value::Float(0.0),
+        "char" => Value::from("x"),
+        "IpAddr" | "Ipv4Addr" => Value::from("127.0.0.1"),
+        "Ipv6Addr" => Value::from("::1"),
+        "SocketAddr" | "SocketAddrV4" => Value::from("127.0.0.1:8080"),
+        "SocketAddrV6" => Value::from("[::1]:8080"),
+        // The way serde writes a `Duration`
+        "Duration" => Value::Struct(
+            "Duration".to_string(),
+            vec![
+                ("secs".to_string(), Value::Integer(0)),
+                ("nanos".to_string(), Value::Integer(0)),
+            ],
+        ),
+        "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => Value::Array(Vec::new()),
+        "HashMap" | "BTreeMap" => Value::Table(Vec::new()),
+        _ => Value::String(String::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "properties")]
+    #[test]
+    fn unwritable_example_is_an_error() {
+        let server = Value::Table(vec![("host".to_string(), Value::String("a".to_string()))]);
+        let fields = [FieldInfo::new("servers")
+            .field_type("Vec<Server>")
+            .default(Value::Array(vec![server]))];
+
+        let options = ExampleOptions::new(ConfigFormat::Properties);
+        let err = try_generate_example(&fields, &options).unwrap_err();
+        assert_eq!(err.path, "servers");
+        assert_eq!(err.reason, "arrays of tables can't be written as flat keys");
+    }
+
+    /// A root key of each kind, and a section
+    #[cfg(feature = "toml")]
+    fn fields() -> Vec<FieldInfo> {
+        vec![
+            FieldInfo::new("name")
+                .doc("The name\nof the app")
+                .field_type("String")
+                .required(true),
+            FieldInfo::new("port")
+                .doc("Port")
+                .field_type("u16")
+                .default(Value::Integer(8080)),
+            FieldInfo::new("tags")
+                .field_type("Vec<String>")
+                .example(Value::Array(vec![Value::from("a")])),
+            FieldInfo::new("server").doc("Server settings").nested(vec![
+                FieldInfo::new("host")
+                    .field_type("String")
+                    .default(Value::from("localhost")),
+                FieldInfo::new("timeout").field_type("Option<u64>"),
+            ]),
+        ]
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn example_holds_every_key_with_its_docs() {
+        let options = ExampleOptions::new(ConfigFormat::Toml);
+        let example = generate_example(&fields(), &options);
+        assert_eq!(
+            example,
+            r#"# The name
+# of the app
+# Required
+name = ""
+
+# Port
+# Default: 8080
+port = 8080
+
+# Optional
+tags = ["a"]
+
+# Server settings
+[server]
+
+# Default: "localhost"
+host = "localhost"
+
+# Optional
+# timeout = 0
+"#
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn commented_out_style_keeps_required_keys_and_examples_active() {
+        let options = ExampleOptions::new(ConfigFormat::Toml).defaults(DefaultsStyle::CommentedOut);
+        let example = generate_example(&fields(), &options);
+        assert_eq!(
+            example,
+            r#"# The name
+# of the app
+# Required
+name = ""
+
+# Port
+# port = 8080
+
+# Optional
+tags = ["a"]
+
+# Server settings
+[server]
+
+# host = "localhost"
+
+# Optional
+# timeout = 0
+"#
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct App {
+        name: String,
+        #[serde(default)]
+        port: u16,
+        server: Server,
+    }
+
+    #[cfg(feature = "toml")]
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Server {
+        #[serde(default)]
+        host: String,
+    }
+
+    #[cfg(feature = "toml")]
+    impl ConfigDocsStruct for App {
+        fn schema() -> crate::ConfigSchema {
+            crate::ConfigSchema::builder()
+                .name("App")
+                .add_field(FieldInfo::new("name").field_type("String").required(true))
+                .add_field(
+                    FieldInfo::new("port")
+                        .field_type("u16")
+                        .default(Value::Integer(8080)),
+                )
+                .add_field(FieldInfo::new("server").nested(vec![FieldInfo::new("host")
+                    .field_type("String")
+                    .default(Value::from("localhost"))]))
+                .build()
+        }
+    }
+
+    /// Documents `port` under the name `listen`, as if the field was renamed
+    /// without updating the docs
+    #[cfg(feature = "toml")]
+    #[derive(Debug, serde::Deserialize)]
+    struct Drifted {
+        #[allow(dead_code)]
+        port: u16,
+    }
+
+    #[cfg(feature = "toml")]
+    impl ConfigDocsStruct for Drifted {
+        fn schema() -> crate::ConfigSchema {
+            crate::ConfigSchema::builder()
+                .name("Drifted")
+                .add_field(FieldInfo::new("listen").field_type("u16").required(true))
+                .build()
+        }
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn verified_example_deserializes_into_the_type() {
+        let options = ExampleOptions::new(ConfigFormat::Toml);
+        let app = verify_example_config::<App>(&options).unwrap();
+        assert_eq!(
+            app,
+            App {
+                name: String::new(),
+                port: 8080,
+                server: Server {
+                    host: "localhost".to_string(),
+                },
+            }
+        );
+
+        let options = options.defaults(DefaultsStyle::CommentedOut);
+        let app = verify_example_config::<App>(&options).unwrap();
+        assert_eq!(app.port, 0);
+        assert_eq!(app.server.host, "");
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn drifted_example_is_an_error_showing_the_example() {
+        let options = ExampleOptions::new(ConfigFormat::Toml);
+        let err = verify_example_config::<Drifted>(&options).unwrap_err();
+        assert!(err.type_name.ends_with("Drifted"));
+        assert!(
+            err.message.contains("missing field `port`"),
+            "{}",
+            err.message
+        );
+        assert_eq!(err.example, "# Required\nlisten = 0\n");
+
+        let message = err.to_string();
+        assert!(message.starts_with("the generated toml example for `"));
+        assert!(
+            message.ends_with("   1 | # Required\n   2 | listen = 0\n"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn placeholder_matches_the_type() {
+        assert_eq!(placeholder_value("Option<u16>"), Value::Integer(0));
+        assert_eq!(
+            placeholder_value("std::vec::Vec<String>"),
+            Value::Array(Vec::new())
+        );
+        assert_eq!(
+            placeholder_value("HashMap<String, u8>"),
+            Value::Table(Vec::new())
+        );
+        assert_eq!(placeholder_value("PathBuf"), Value::String(String::new()));
+    }
+}
@@ -0,0 +1,463 @@
+// serde_config_docs/src/export.rs
+//! Export a root type's docs to files, as `#[config_docs(export)]` does
+//!
+//! The test generated by the derive macro and the `cargo config-docs` runner
+//! both go through [`export_docs`], so they name and check files the same way.
+
+use crate::{
+    inject_docs, try_generate_combined_docs, update_docs_file, ConfigFormat, ConfigSchema,
+    DocsError, DocsMode, FormatNameError, InjectError, MarkdownOptions, ShapeError,
+};
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where and how a root type's docs are exported, as set by its
+/// `#[config_docs(export(...))]` and `#[config_docs(inject = "...")]`
+/// attributes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportSettings {
+    /// Write the docs to files of their own
+    pub files: bool,
+    /// Where to write the docs, relative to the crate, instead of
+    /// `docs/<Type>.<ext>.md`
+    pub path: Option<&'static str>,
+    /// The names of the formats to export, or none for the one named by
+    /// `CONFIG_DOCS_FORMAT`
+    pub formats: &'static [&'static str],
+    /// The title of the generated document
+    pub title: Option<&'static str>,
+    /// Also write the docs split into one page per section
+    pub pages: bool,
+    /// Always check the docs on disk instead of writing them
+    pub check: bool,
+    /// Other root types to document after this one, in the same document
+    pub combine: &'static [fn() -> ConfigSchema],
+    /// A hand-written Markdown file to inject the docs into, relative to the
+    /// crate
+    pub inject: Option<&'static str>,
+}
+
+impl ExportSettings {
+    /// The formats to export: the one named by `CONFIG_DOCS_FORMAT` if it's
+    /// set, else the listed ones, else the first built-in format
+    pub fn formats(&self) -> Result<Vec<ConfigFormat>, FormatNameError> {
+        let overridden = std::env::var("CONFIG_DOCS_FORMAT").is_ok_and(|name| !name.is_empty());
+        if self.formats.is_empty() || overridden {
+            return ConfigFormat::from_env().map(|format| vec![format]);
+        }
+        self.formats.iter().map(|name| name.parse()).collect()
+    }
+
+    /// The format docs are injected in: the first listed format, else the
+    /// first built-in one
+    ///
+    /// `CONFIG_DOCS_FORMAT` doesn't change it, since the hand-written file
+    /// can only hold the docs in one format.
+    pub fn inject_format(&self) -> Result<ConfigFormat, FormatNameError> {
+        match self.formats.first() {
+            Some(name) => name.parse(),
+            None => ConfigFormat::builtin()
+                .first()
+                .copied()
+                .ok_or(FormatNameError::NoneEnabled),
+        }
+    }
+
+    /// Check the docs if `check` is set, and otherwise as `CONFIG_DOCS_CHECK`
+    /// says
+    pub fn mode(&self) -> DocsMode {
+        if self.check {
+            DocsMode::Check
+        } else {
+            DocsMode::from_env()
+        }
+    }
+
+    /// The file the docs of the type called `name` are written to in
+    /// `format`, under the crate's directory `root`
+    ///
+    /// A custom `path` is used as it is when it's written in the only format
+    /// the settings list. Every other file gets the format's extension, e.g.
+    /// `book/config.yaml.md`, so exporting another format, e.g. with
+    /// `CONFIG_DOCS_FORMAT`, never overwrites it.
+    pub fn file_path(&self, root: &Path, name: &str, format: ConfigFormat) -> PathBuf {
+        match self.path {
+            Some(path) if self.pins(format) => root.join(path),
+            Some(path) => {
+                let path = root.join(path);
+                let extension = path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .unwrap_or("md");
+                path.with_extension(format!("{}.{}", format.extension(), extension))
+            }
+            None => root
+                .join("docs")
+                .join(format!("{}.{}.md", name, format.extension())),
+        }
+    }
+
+    /// The directory the pages of the type called `name` are written to in
+    /// `format`, next to its docs file
+    ///
+    /// The directory is named after the type, e.g. `docs/Config/`, so the
+    /// types of combined docs each keep pages of their own. Pages in a format
+    /// the settings don't pin go one level deeper, e.g. `docs/Config/yaml/`.
+    pub fn pages_dir(&self, root: &Path, name: &str, format: ConfigFormat) -> PathBuf {
+        let file_path = self.file_path(root, name, format);
+        let dir = file_path.parent().unwrap_or(root).join(name);
+        if self.pins(format) {
+            dir
+        } else {
+            dir.join(format.extension())
+        }
+    }
+
+    /// Whether `format` is the only format the settings list
+    fn pins(&self, format: ConfigFormat) -> bool {
+        match self.formats {
+            [name] => ConfigFormat::from_name(name)
+                .is_some_and(|pinned| pinned.extension() == format.extension()),
+            _ => false,
+        }
+    }
+}
+
+/// Export the docs of the root type with the given schema to files under the
+/// crate's directory `root`, or check them in check mode, and return the
+/// paths of the files
+///
+/// Docs are injected into the hand-written file in the settings'
+/// [`inject_format`](ExportSettings::inject_format), whatever `formats` holds.
+/// Every file is written or checked before failing, so the error lists all
+/// of the stale ones.
+pub fn export_docs(
+    root: impl AsRef<Path>,
+    schema: fn() -> ConfigSchema,
+    settings: &ExportSettings,
+    formats: &[ConfigFormat],
+    mode: DocsMode,
+) -> Result<Vec<PathBuf>, ExportError> {
+    let root = root.as_ref();
+    let schemas: Vec<ConfigSchema> = std::iter::once(schema)
+        .chain(settings.combine.iter().copied())
+        .map(|schema| schema())
+        .collect();
+    let name = schemas[0].name.as_deref().unwrap_or("Config");
+
+    let mut paths = Vec::new();
+    let mut errors = Vec::new();
+    let mut update = |path: PathBuf, contents: &str| match update_docs_file(&path, contents, mode) {
+        Ok(()) => paths.push(path),
+        Err(err) => errors.push(err),
+    };
+
+    let options = |format| {
+        let mut options = MarkdownOptions::new(format);
+        options.title = settings.title.map(str::to_string);
+        options
+    };
+    let docs = |options: &MarkdownOptions| {
+        match schemas.as_slice() {
+            [schema] => schema.try_generate_docs_with_options(options),
+            schemas => try_generate_combined_docs(schemas, options),
+        }
+        .map_err(ExportError::Shape)
+    };
+
+    if settings.files {
+        for &format in formats {
+            let options = options(format);
+            let docs = docs(&options)?;
+            let file_path = settings.file_path(root, name, format);
+            update(file_path, &docs);
+
+            if settings.pages {
+                for schema in &schemas {
+                    let name = schema.name.as_deref().unwrap_or("Config");
+                    let pages_dir = settings.pages_dir(root, name, format);
+                    let pages = schema
+                        .try_generate_doc_pages(&options)
+                        .map_err(ExportError::Shape)?;
+                    for (page_name, page) in pages {
+                        update(pages_dir.join(page_name), &page);
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(inject) = settings.inject {
+        let path = root.join(inject);
+        let inject_error = |error| ExportError::Inject {
+            path: path.clone(),
+            error,
+        };
+        let format = settings.inject_format().map_err(ExportError::Format)?;
+        let docs = docs(&options(format))?;
+        let document =
+            fs::read_to_string(&path).map_err(|err| inject_error(InjectError::Io(err)))?;
+        let injected = inject_docs(&document, name, &docs).map_err(inject_error)?;
+        update(path.clone(), &injected);
+    }
+
+    if errors.is_empty() {
+        Ok(paths)
+    } else {
+        Err(ExportError::Docs(errors))
+    }
+}
+
+/// Error returned when a root type's docs can't be exported
+#[derive(Debug)]
+pub enum ExportError {
+    /// A listed format isn't known or its feature isn't enabled
+    Format(FormatNameError),
+    /// The docs can't be written in one of the formats
+    Shape(ShapeError),
+    /// The docs can't be injected into the hand-written file at `path`
+    Inject { path: PathBuf, error: InjectError },
+    /// Files that couldn't be written, or in check mode are stale or missing
+    Docs(Vec<DocsError>),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Format(err) => write!(f, "export formats: {}", err),
+            ExportError::Shape(err) => write!(f, "{}", err),
+            ExportError::Inject { path, error } => write!(f, "{}: {}", path.display(), error),
+            ExportError::Docs(errors) => {
+                for (index, err) in errors.iter().enumerate() {
+                    if index > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", err)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExportError::Format(err) => Some(err),
+            ExportError::Shape(err) => Some(err),
+            ExportError::Inject { error, .. } => Some(error),
+            ExportError::Docs(_) => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "toml", feature = "yaml"))]
+mod tests {
+    use super::*;
+    use crate::FieldInfo;
+
+    fn schema() -> ConfigSchema {
+        ConfigSchema::builder()
+            .name("Config")
+            .add_field(FieldInfo::new("port").field_type("u16"))
+            .add_field(
+                FieldInfo::new("server").nested(vec![FieldInfo::new("host").field_type("String")]),
+            )
+            .build()
+    }
+
+    fn export(root: &Path, settings: &ExportSettings, format: ConfigFormat, mode: DocsMode) {
+        if let Err(err) = export_docs(root, schema, settings, &[format], mode) {
+            panic!("{}", err);
+        }
+    }
+
+    #[test]
+    fn file_names_keep_formats_apart() {
+        let root = Path::new("crate");
+        let path = |settings: ExportSettings, format| settings.file_path(root, "Config", format);
+
+        let default = ExportSettings::default();
+        assert_eq!(
+            path(default, ConfigFormat::Toml),
+            root.join("docs/Config.toml.md")
+        );
+
+        let pinned = ExportSettings {
+            path: Some("book/config.md"),
+            formats: &["toml"],
+            ..default
+        };
+        assert_eq!(
+            path(pinned, ConfigFormat::Toml),
+            root.join("book/config.md")
+        );
+        assert_eq!(
+            path(pinned, ConfigFormat::Yaml),
+            root.join("book/config.yaml.md")
+        );
+
+        for formats in [&[][..], &["toml", "yaml"][..]] {
+            let custom = ExportSettings {
+                path: Some("book/config.md"),
+                formats,
+                ..default
+            };
+            assert_eq!(
+                path(custom, ConfigFormat::Toml),
+                root.join("book/config.toml.md")
+            );
+        }
+    }
+
+    #[test]
+    fn exporting_another_format_keeps_the_first_one_up_to_date() {
+        let default = ExportSettings {
+            files: true,
+            pages: true,
+            ..ExportSettings::default()
+        };
+        let custom = ExportSettings {
+            path: Some("book/config.md"),
+            formats: &["toml"],
+            ..default
+        };
+
+        for settings in [default, custom] {
+            let dir = tempfile::tempdir().unwrap();
+            // As with a run of the tests, then one with `CONFIG_DOCS_FORMAT=yaml`
+            export(dir.path(), &settings, ConfigFormat::Toml, DocsMode::Write);
+            export(dir.path(), &settings, ConfigFormat::Yaml, DocsMode::Write);
+
+            export(dir.path(), &settings, ConfigFormat::Toml, DocsMode::Check);
+            export(dir.path(), &settings, ConfigFormat::Yaml, DocsMode::Check);
+
+            for format in [ConfigFormat::Toml, ConfigFormat::Yaml] {
+                let pages = settings.pages_dir(dir.path(), "Config", format);
+                assert!(pages.join("server.md").exists(), "{}", pages.display());
+            }
+        }
+    }
+
+    #[test]
+    fn pages_are_named_after_the_type() {
+        let root = Path::new("crate");
+        let default = ExportSettings::default();
+        assert_eq!(
+            default.pages_dir(root, "Config", ConfigFormat::Toml),
+            root.join("docs/Config/toml")
+        );
+
+        let pinned = ExportSettings {
+            path: Some("book/config.md"),
+            formats: &["toml"],
+            ..default
+        };
+        assert_eq!(
+            pinned.pages_dir(root, "Config", ConfigFormat::Toml),
+            root.join("book/Config")
+        );
+        assert_eq!(
+            pinned.pages_dir(root, "Config", ConfigFormat::Yaml),
+            root.join("book/Config/yaml")
+        );
+    }
+
+    #[test]
+    fn check_lists_every_stale_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = ExportSettings {
+            files: true,
+            pages: true,
+            ..ExportSettings::default()
+        };
+        let formats = [ConfigFormat::Toml, ConfigFormat::Yaml];
+        let paths = export_docs(dir.path(), schema, &settings, &formats, DocsMode::Write).unwrap();
+        assert_eq!(paths.len(), 6);
+
+        fs::write(&paths[0], "stale").unwrap();
+        fs::remove_file(&paths[3]).unwrap();
+        match export_docs(dir.path(), schema, &settings, &formats, DocsMode::Check) {
+            Err(ExportError::Docs(errors)) => {
+                assert!(matches!(&errors[0], DocsError::Stale { path, .. } if *path == paths[0]));
+                assert!(matches!(&errors[1], DocsError::Missing { path } if *path == paths[3]));
+                assert_eq!(errors.len(), 2);
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert_eq!(fs::read_to_string(&paths[0]).unwrap(), "stale");
+    }
+
+    #[test]
+    fn injected_docs_keep_their_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let readme = dir.path().join("README.md");
+        fs::write(
+            &readme,
+            "<!-- config-docs:start Config -->\n<!-- config-docs:end -->\n",
+        )
+        .unwrap();
+        let settings = ExportSettings {
+            inject: Some("README.md"),
+            ..ExportSettings::default()
+        };
+
+        export(dir.path(), &settings, ConfigFormat::Yaml, DocsMode::Write);
+        export(dir.path(), &settings, ConfigFormat::Toml, DocsMode::Check);
+        assert!(fs::read_to_string(&readme).unwrap().contains("```toml"));
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn combined_docs_are_named_after_the_first_type() {
+        fn worker() -> ConfigSchema {
+            ConfigSchema::builder()
+                .name("Worker")
+                .add_field(FieldInfo::new("threads").field_type("u8"))
+                .build()
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let settings = ExportSettings {
+            files: true,
+            combine: &[worker],
+            ..ExportSettings::default()
+        };
+        export(dir.path(), &settings, ConfigFormat::Toml, DocsMode::Write);
+
+        let file = settings.file_path(dir.path(), "Config", ConfigFormat::Toml);
+        let docs = fs::read_to_string(file).unwrap();
+        let config = docs.find("## Config\n").unwrap();
+        let worker = docs.find("## Worker\n").unwrap();
+        assert!(config < worker, "{}", docs);
+        assert!(docs.contains("threads = 0"), "{}", docs);
+    }
+
+    #[test]
+    fn combined_types_get_pages_of_their_own() {
+        fn worker() -> ConfigSchema {
+            ConfigSchema::builder()
+                .name("Worker")
+                .add_field(FieldInfo::new("queue").nested(vec![FieldInfo::new("size")]))
+                .build()
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let settings = ExportSettings {
+            files: true,
+            pages: true,
+            formats: &["toml"],
+            combine: &[worker],
+            ..ExportSettings::default()
+        };
+        export(dir.path(), &settings, ConfigFormat::Toml, DocsMode::Write);
+
+        let config = settings.pages_dir(dir.path(), "Config", ConfigFormat::Toml);
+        let worker = settings.pages_dir(dir.path(), "Worker", ConfigFormat::Toml);
+        assert!(config.join("server.md").exists());
+        assert!(worker.join("queue.md").exists());
+        assert!(!config.join("queue.md").exists());
+    }
+}
@@ -0,0 +1,795 @@
+// serde_config_docs/src/flat.rs
+//! Flat key/value formats for systems that only take INI or `.properties`
+//!
+//! INI files group keys under `[server.tls]` section headers, while Java
+//! properties spell out the full dotted path on every key, e.g.
+//! `server.tls.cert=...`. Neither has lists, so scalar lists are written
+//! comma-separated and anything deeper is rejected with a [`ShapeError`].
+
+use crate::flat_value::flat_text;
+use crate::render::{
+    join_path, resolve_key, write_annotation, write_comment_lines, RenderContext, Renderer,
+};
+use crate::{ConfigFormat, ConfigSchema, DefaultsStyle, FieldInfo, ShapeError, Value, VariantData};
+
+use std::fmt::{self, Write};
+
+/// Which flat format to read or write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Flavor {
+    #[cfg(feature = "ini")]
+    Ini,
+    #[cfg(feature = "properties")]
+    Properties,
+}
+
+impl Renderer for Flavor {
+    fn name(&self) -> &str {
+        match *self {
+            #[cfg(feature = "ini")]
+            Flavor::Ini => "ini",
+            #[cfg(feature = "properties")]
+            Flavor::Properties => "properties",
+        }
+    }
+
+    fn extension(&self) -> &str {
+        self.name()
+    }
+
+    fn comment_marker(&self) -> Option<&str> {
+        Some(self.marker())
+    }
+
+    fn render_value(&self, value: &Value) -> String {
+        render_value(*self, value)
+    }
+
+    fn render_document(&self, value: &Value) -> Result<String, ShapeError> {
+        format_value(*self, value)
+    }
+
+    fn validate(&self, fields: &[FieldInfo]) -> Result<(), ShapeError> {
+        validate(*self, fields, "")
+    }
+
+    fn write_section(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        section: &FieldInfo,
+        path: &[&str],
+    ) -> fmt::Result {
+        let path = path.join(".");
+        if self.has_headers() {
+            write_section_header(buffer, &path)?;
+        }
+        write_section_keys(buffer, *self, &section.nested_fields, &path, ctx.defaults)
+    }
+
+    fn write_document(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        fields: &[FieldInfo],
+    ) -> fmt::Result {
+        write_table(buffer, *self, fields, "", ctx.defaults)
+    }
+
+    fn parse(&self, input: &str, schema: Option<&ConfigSchema>) -> Result<Value, String> {
+        let sections = schema
+            .map(|schema| schema.fields.as_slice())
+            .unwrap_or_default();
+        parse(*self, input, sections)
+    }
+}
+
+impl Flavor {
+    fn format(self) -> ConfigFormat {
+        match self {
+            #[cfg(feature = "ini")]
+            Flavor::Ini => ConfigFormat::Ini,
+            #[cfg(feature = "properties")]
+            Flavor::Properties => ConfigFormat::Properties,
+        }
+    }
+
+    /// Whether keys are grouped under section headers, rather than spelling
+    /// out their full path
+    fn has_headers(self) -> bool {
+        match self {
+            #[cfg(feature = "ini")]
+            Flavor::Ini => true,
+            #[cfg(feature = "properties")]
+            Flavor::Properties => false,
+        }
+    }
+
+    fn marker(self) -> &'static str {
+        match self {
+            #[cfg(feature = "ini")]
+            Flavor::Ini => ";",
+            #[cfg(feature = "properties")]
+            Flavor::Properties => "#",
+        }
+    }
+
+    /// Escape a full dotted key
+    fn key(self, key: &str) -> String {
+        match self {
+            #[cfg(feature = "ini")]
+            Flavor::Ini => key.to_string(),
+            #[cfg(feature = "properties")]
+            Flavor::Properties => properties_escape(key, true),
+        }
+    }
+
+    /// Escape or quote a value so it reads back as the same text
+    fn value(self, text: &str) -> String {
+        match self {
+            #[cfg(feature = "ini")]
+            Flavor::Ini => ini_value(text),
+            #[cfg(feature = "properties")]
+            Flavor::Properties => properties_escape(text, false),
+        }
+    }
+
+    /// Why a single key segment can't be written, if it can't
+    fn check_key(self, key: &str) -> Option<&'static str> {
+        if key.is_empty() {
+            return Some("empty keys can't be written as flat keys");
+        }
+        if key.contains('.') {
+            return Some("keys can't contain `.`, since it separates sections");
+        }
+
+        match self {
+            #[cfg(feature = "ini")]
+            Flavor::Ini => {
+                let is_plain =
+                    key.trim() == key && !key.contains(['=', ';', '#', '[', ']', '"', '\n', '\r']);
+                (!is_plain).then_some(
+                    "INI keys can't contain `=`, `;`, `#`, `[`, `]`, quotes, line breaks \
+                     or surrounding whitespace",
+                )
+            }
+            #[cfg(feature = "properties")]
+            Flavor::Properties => None,
+        }
+    }
+}
+
+/// Check that every key and value below these fields can be written
+fn validate(flavor: Flavor, fields: &[FieldInfo], path: &str) -> Result<(), ShapeError> {
+    for field in fields {
+        let current_path = join_path(path, &field.name);
+
+        if let Some(reason) = flavor.check_key(&field.name) {
+            return Err(shape_error(flavor, current_path, reason));
+        }
+
+        if field.is_nested {
+            validate(flavor, &field.nested_fields, &current_path)?;
+            continue;
+        }
+
+        for value in [&field.default_value, &field.example_value]
+            .into_iter()
+            .flatten()
+        {
+            flatten(flavor, &current_path, value, &mut Vec::new())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Write the keys of a table, followed by its sections in nesting order
+fn write_table(
+    buffer: &mut String,
+    flavor: Flavor,
+    fields: &[FieldInfo],
+    path: &str,
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    write_section_keys(buffer, flavor, fields, path, defaults)?;
+
+    for field in fields.iter().filter(|f| f.is_nested) {
+        let current_path = join_path(path, &field.name);
+
+        write_comment_lines(buffer, field.doc_comments.as_deref(), flavor.marker())?;
+        if flavor.has_headers() {
+            write_section_header(buffer, &current_path)?;
+        } else if field.doc_comments.is_some() {
+            // Keep the section's doc comment apart from its first key
+            writeln!(buffer)?;
+        }
+
+        write_table(
+            buffer,
+            flavor,
+            &field.nested_fields,
+            &current_path,
+            defaults,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Write an INI section header, e.g. `[server.tls]`
+fn write_section_header(buffer: &mut String, path: &str) -> fmt::Result {
+    writeln!(buffer, "[{}]", path)?;
+    writeln!(buffer)
+}
+
+/// Write the leaf keys of the section at `path`
+fn write_section_keys(
+    buffer: &mut String,
+    flavor: Flavor,
+    fields: &[FieldInfo],
+    path: &str,
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    // INI keys are relative to the section header above them
+    let prefix = if flavor.has_headers() { "" } else { path };
+
+    for field in fields.iter().filter(|f| !f.is_nested) {
+        write_key(buffer, flavor, field, prefix, defaults)?;
+        writeln!(buffer)?;
+    }
+
+    Ok(())
+}
+
+/// Write a documented key for a leaf field, one line per flattened entry
+fn write_key(
+    buffer: &mut String,
+    flavor: Flavor,
+    field: &FieldInfo,
+    prefix: &str,
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    let marker = flavor.marker();
+    write_comment_lines(buffer, field.doc_comments.as_deref(), marker)?;
+
+    let key = resolve_key(field, defaults);
+    write_annotation(buffer, key.annotation.as_ref(), marker, &flavor)?;
+
+    let mut entries = Vec::new();
+    let name = join_path(prefix, &field.name);
+    // `validate` reports why before anything is written, so there's nothing
+    // to add to a plain formatting error here
+    flatten(flavor, &name, &key.value, &mut entries).map_err(|_| fmt::Error)?;
+
+    for (name, text) in entries {
+        let line = format!("{}={}", flavor.key(&name), flavor.value(&text));
+        if key.commented {
+            writeln!(buffer, "{} {}", marker, line)?;
+        } else {
+            writeln!(buffer, "{}", line)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Render a value on a single line, as written after a key
+///
+/// Values spread over several keys are shown as the keys below this one,
+/// e.g. `ka=v, kb=w` for a map written as `labels.ka=v` and `labels.kb=w`.
+fn render_value(flavor: Flavor, value: &Value) -> String {
+    // Anything that doesn't flatten is rejected by `validate` before it's
+    // written, so the keys that did flatten are all there is to show
+    let mut entries = Vec::new();
+    let _ = flatten(flavor, "", value, &mut entries);
+
+    match entries.as_slice() {
+        // An empty value is nothing after the `=`, which an annotation or a
+        // table cell wouldn't show
+        [] => "(empty)".to_string(),
+        [(key, text)] if key.is_empty() && text.is_empty() => "(empty)".to_string(),
+        [(key, text)] if key.is_empty() => flavor.value(text),
+        entries => entries
+            .iter()
+            .map(|(key, text)| format!("{}={}", flavor.key(key), flavor.value(text)))
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// Render a whole value as flat `key=value` lines
+fn format_value(flavor: Flavor, value: &Value) -> Result<String, ShapeError> {
+    let mut entries = Vec::new();
+    flatten(flavor, "", value, &mut entries)?;
+
+    let mut buffer = String::new();
+    for (key, text) in entries {
+        writeln!(buffer, "{}={}", flavor.key(&key), flavor.value(&text)).unwrap();
+    }
+    Ok(buffer)
+}
+
+/// Flatten a value into `(dotted key, text)` entries below `key`
+///
+/// Tables become dotted keys, scalar lists are joined with commas, and enum
+/// variants with data are written the way serde tags them, e.g.
+/// `mode.Custom.threads=4`.
+fn flatten(
+    flavor: Flavor,
+    key: &str,
+    value: &Value,
+    entries: &mut Vec<(String, String)>,
+) -> Result<(), ShapeError> {
+    match value {
+        // Nothing to write, which is how serde leaves out `None` as well
+        Value::Null => {}
+        Value::Table(items) | Value::Struct(_, items) if !items.is_empty() => {
+            for (name, item) in items {
+                if let Some(reason) = flavor.check_key(name) {
+                    return Err(shape_error(flavor, join_path(key, name), reason));
+                }
+                flatten(flavor, &join_path(key, name), item, entries)?;
+            }
+        }
+        Value::Variant(name, data) if !matches!(data.as_ref(), VariantData::Unit) => {
+            if let Some(reason) = flavor.check_key(name) {
+                return Err(shape_error(flavor, join_path(key, name), reason));
+            }
+
+            let data = match data.as_ref() {
+                VariantData::Unit => unreachable!(),
+                VariantData::Newtype(value) => value.clone(),
+                VariantData::Tuple(items) => Value::Array(items.clone()),
+                VariantData::Struct(items) => Value::Table(items.clone()),
+            };
+            flatten(flavor, &join_path(key, name), &data, entries)?;
+        }
+        value => {
+            let text =
+                flat_text(value).map_err(|reason| shape_error(flavor, key.into(), reason))?;
+            entries.push((key.to_string(), text));
+        }
+    }
+
+    Ok(())
+}
+
+fn shape_error(flavor: Flavor, path: String, reason: &str) -> ShapeError {
+    ShapeError {
+        format: flavor.format(),
+        path,
+        reason: reason.to_string(),
+    }
+}
+
+/// Parse a flat document into a tree of tables, with all values as strings
+///
+/// `sections` are created up front, since properties files can't write a
+/// section whose keys are all commented out.
+fn parse(flavor: Flavor, input: &str, sections: &[FieldInfo]) -> Result<Value, String> {
+    let mut root = Vec::new();
+    seed_sections(&mut root, sections)?;
+
+    match flavor {
+        #[cfg(feature = "ini")]
+        Flavor::Ini => parse_ini(&mut root, input)?,
+        #[cfg(feature = "properties")]
+        Flavor::Properties => parse_properties(&mut root, input)?,
+    }
+
+    Ok(Value::Table(root))
+}
+
+fn seed_sections(table: &mut Vec<(String, Value)>, fields: &[FieldInfo]) -> Result<(), String> {
+    for field in fields.iter().filter(|f| f.is_nested) {
+        insert(table, &[field.name.as_str()], None)?;
+        if let Some((_, Value::Table(inner))) = table.iter_mut().find(|(k, _)| *k == field.name) {
+            seed_sections(inner, &field.nested_fields)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "ini")]
+fn parse_ini(root: &mut Vec<(String, Value)>, input: &str) -> Result<(), String> {
+    let mut section = String::new();
+
+    for (number, line) in input.lines().enumerate() {
+        let line = line.trim();
+        let at_line = |message: String| format!("line {}: {}", number + 1, message);
+
+        if line.is_empty() || line.starts_with([';', '#']) {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            let path: Vec<&str> = section.split('.').collect();
+            insert(root, &path, None).map_err(at_line)?;
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(at_line(format!("expected `key=value`, found `{}`", line)));
+        };
+
+        let key = join_path(&section, key.trim());
+        let value = value.trim();
+        let value = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+            Some(quoted) => unescape(quoted),
+            None => value.to_string(),
+        };
+
+        let path: Vec<&str> = key.split('.').collect();
+        insert(root, &path, Some(value)).map_err(at_line)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "properties")]
+fn parse_properties(root: &mut Vec<(String, Value)>, input: &str) -> Result<(), String> {
+    let mut lines = input.lines().enumerate();
+
+    while let Some((number, line)) = lines.next() {
+        let at_line = |message: String| format!("line {}: {}", number + 1, message);
+
+        let mut line = line.trim_start().to_string();
+        if line.is_empty() || line.starts_with(['#', '!']) {
+            continue;
+        }
+
+        // An odd number of trailing backslashes continues the line
+        while line.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1 {
+            line.pop();
+            match lines.next() {
+                Some((_, next)) => line.push_str(next.trim_start()),
+                None => break,
+            }
+        }
+
+        let mut split = line.len();
+        let mut chars = line.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '=' | ':' | ' ' | '\t' | '\x0c' => {
+                    split = i;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let key = unescape(&line[..split]);
+        let rest = line[split..].trim_start_matches([' ', '\t', '\x0c']);
+        let rest = rest.strip_prefix(['=', ':']).unwrap_or(rest);
+        let value = unescape(rest.trim_start_matches([' ', '\t', '\x0c']));
+
+        let path: Vec<&str> = key.split('.').collect();
+        insert(root, &path, Some(value)).map_err(at_line)?;
+    }
+
+    Ok(())
+}
+
+/// Insert a value at a dotted path, or just the table if `value` is `None`
+fn insert(
+    table: &mut Vec<(String, Value)>,
+    path: &[&str],
+    value: Option<String>,
+) -> Result<(), String> {
+    let (name, rest) = path.split_first().expect("paths are never empty");
+    let position = table.iter().position(|(key, _)| key == name);
+
+    if rest.is_empty() {
+        return match (value, position) {
+            (Some(_), Some(_)) => Err(format!("`{}` is set more than once", name)),
+            (Some(text), None) => {
+                table.push((name.to_string(), Value::String(text)));
+                Ok(())
+            }
+            (None, None) => {
+                table.push((name.to_string(), Value::Table(Vec::new())));
+                Ok(())
+            }
+            (None, Some(i)) => as_table(&mut table[i]).map(|_| ()),
+        };
+    }
+
+    let i = position.unwrap_or_else(|| {
+        table.push((name.to_string(), Value::Table(Vec::new())));
+        table.len() - 1
+    });
+    insert(as_table(&mut table[i])?, rest, value)
+}
+
+/// The table held by an entry, turning an empty value into an empty table
+fn as_table(entry: &mut (String, Value)) -> Result<&mut Vec<(String, Value)>, String> {
+    if matches!(&entry.1, Value::String(text) if text.is_empty()) {
+        entry.1 = Value::Table(Vec::new());
+    }
+
+    match &mut entry.1 {
+        Value::Table(inner) => Ok(inner),
+        _ => Err(format!("`{}` is both a value and a section", entry.0)),
+    }
+}
+
+/// Quote an INI value if reading it back would otherwise change it
+#[cfg(feature = "ini")]
+fn ini_value(text: &str) -> String {
+    let needs_quotes = text.trim() != text || text.starts_with('"') || text.contains(['\n', '\r']);
+    if !needs_quotes {
+        return text.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Escape a properties key or value, following `java.util.Properties`
+#[cfg(feature = "properties")]
+fn properties_escape(text: &str, is_key: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\x0c' => escaped.push_str("\\f"),
+            // Leading spaces in values would be skipped as separator
+            ' ' if is_key || i == 0 => escaped.push_str("\\ "),
+            '=' | ':' | '#' | '!' if is_key => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Undo backslash escapes, as used by both quoted INI values and properties
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some('f') => unescaped.push('\x0c'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(c) => unescaped.push(c),
+                    None => unescaped.push_str(&hex),
+                }
+            }
+            Some(c) => unescaped.push(c),
+            None => {}
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_example, ExampleOptions};
+
+    fn text(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    fn table(entries: &[(&str, Value)]) -> Value {
+        Value::Table(
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect(),
+        )
+    }
+
+    /// Write an example for the fields and read it back
+    fn round_trip(flavor: Flavor, fields: &[FieldInfo]) -> Value {
+        let example = generate_example(fields, &ExampleOptions::new(flavor.format()));
+        flavor
+            .parse(&example, None)
+            .unwrap_or_else(|err| panic!("{}\n{}", err, example))
+    }
+
+    #[cfg(feature = "properties")]
+    #[test]
+    fn properties_escape_separators_in_keys_and_values() {
+        let fields = [FieldInfo::new("a=b:c#d!e f")
+            .field_type("String")
+            .default(text("x=y:z #not a comment"))];
+
+        let example = generate_example(&fields, &ExampleOptions::new(ConfigFormat::Properties));
+        assert!(
+            example.contains("a\\=b\\:c\\#d\\!e\\ f=x=y:z #not a comment"),
+            "{}",
+            example
+        );
+        assert_eq!(
+            round_trip(Flavor::Properties, &fields),
+            table(&[("a=b:c#d!e f", text("x=y:z #not a comment"))])
+        );
+    }
+
+    #[cfg(feature = "properties")]
+    #[test]
+    fn properties_multi_line_values() {
+        let fields = [FieldInfo::new("motd")
+            .field_type("String")
+            .default(text("  first\nsecond\tline"))];
+        assert_eq!(
+            round_trip(Flavor::Properties, &fields),
+            table(&[("motd", text("  first\nsecond\tline"))])
+        );
+
+        // A trailing backslash continues the value on the next line
+        let parsed = Flavor::Properties
+            .parse("motd=first \\\n    second\nother : \\u0041\\\\", None)
+            .unwrap();
+        assert_eq!(
+            parsed,
+            table(&[("motd", text("first second")), ("other", text("A\\"))])
+        );
+    }
+
+    #[cfg(feature = "ini")]
+    #[test]
+    fn ini_quotes_values_that_would_change() {
+        let fields = [
+            FieldInfo::new("motd")
+                .field_type("String")
+                .default(text(" first\nsecond ")),
+            FieldInfo::new("query")
+                .field_type("String")
+                .default(text("a=b ; #c")),
+        ];
+
+        let example = generate_example(&fields, &ExampleOptions::new(ConfigFormat::Ini));
+        assert!(example.contains("motd=\" first\\nsecond \""), "{}", example);
+        assert!(example.contains("query=a=b ; #c"), "{}", example);
+        assert_eq!(
+            round_trip(Flavor::Ini, &fields),
+            table(&[
+                ("motd", text(" first\nsecond ")),
+                ("query", text("a=b ; #c")),
+            ])
+        );
+    }
+
+    #[cfg(feature = "ini")]
+    #[test]
+    fn ini_rejects_keys_it_cant_write() {
+        for key in ["a=b", "a;b", "a#b", "[a]", " a", "a\nb", "a.b", ""] {
+            let fields = [FieldInfo::new(key).field_type("u8")];
+            let err = Flavor::Ini.validate(&fields).unwrap_err();
+            assert_eq!(err.path, key);
+        }
+
+        // Keys inside maps are checked as well
+        let fields = [FieldInfo::new("labels")
+            .field_type("HashMap<String, String>")
+            .default(table(&[("a=b", text("c"))]))];
+        let err = Flavor::Ini.validate(&fields).unwrap_err();
+        assert_eq!(err.path, "labels.a=b");
+    }
+
+    #[cfg(feature = "ini")]
+    #[test]
+    fn ini_repeated_sections_are_merged() {
+        let input = "[server]\nhost=a\n\n[log]\nlevel=info\n\n[server]\nport=80\n";
+        assert_eq!(
+            Flavor::Ini.parse(input, None).unwrap(),
+            table(&[
+                (
+                    "server",
+                    table(&[("host", text("a")), ("port", text("80"))])
+                ),
+                ("log", table(&[("level", text("info"))])),
+            ])
+        );
+    }
+
+    #[cfg(feature = "ini")]
+    #[test]
+    fn ini_reports_malformed_input_by_line() {
+        let err = Flavor::Ini
+            .parse("[server]\nport=80\nport=81\n", None)
+            .unwrap_err();
+        assert_eq!(err, "line 3: `port` is set more than once");
+
+        let err = Flavor::Ini.parse("; comment\nport 80\n", None).unwrap_err();
+        assert_eq!(err, "line 2: expected `key=value`, found `port 80`");
+
+        let err = Flavor::Ini.parse("server=1\n[server]\n", None).unwrap_err();
+        assert_eq!(err, "line 2: `server` is both a value and a section");
+    }
+
+    #[cfg(feature = "properties")]
+    #[test]
+    fn shape_errors_name_the_offending_key() {
+        let server = table(&[("host", text("a"))]);
+        let fields = [
+            FieldInfo::new("cluster").nested(vec![FieldInfo::new("servers")
+                .field_type("Vec<Server>")
+                .default(Value::Array(vec![server]))]),
+        ];
+
+        let err = Flavor::Properties.validate(&fields).unwrap_err();
+        assert_eq!(err.path, "cluster.servers");
+        assert_eq!(err.reason, "arrays of tables can't be written as flat keys");
+        assert_eq!(
+            err.to_string(),
+            "`cluster.servers` can't be written as properties: \
+             arrays of tables can't be written as flat keys"
+        );
+    }
+
+    #[cfg(feature = "ini")]
+    #[test]
+    fn values_over_several_keys_are_shown_in_flat_syntax() {
+        let labels = table(&[("ka", text("v")), ("kb", text("w"))]);
+        assert_eq!(render_value(Flavor::Ini, &labels), "ka=v, kb=w");
+        assert_eq!(render_value(Flavor::Ini, &text(" padded")), "\" padded\"");
+        assert_eq!(
+            render_value(Flavor::Ini, &Value::Array(Vec::new())),
+            "(empty)"
+        );
+        assert_eq!(render_value(Flavor::Ini, &table(&[])), "(empty)");
+
+        let fields = [FieldInfo::new("labels")
+            .field_type("HashMap<String, String>")
+            .default(labels)];
+        let example = generate_example(&fields, &ExampleOptions::new(ConfigFormat::Ini));
+        assert_eq!(example, "; Default: ka=v, kb=w\nlabels.ka=v\nlabels.kb=w\n");
+    }
+
+    #[cfg(feature = "properties")]
+    #[test]
+    fn variants_with_data_are_spread_over_keys() {
+        let mode = Value::Variant(
+            "Custom".to_string(),
+            Box::new(VariantData::Struct(vec![(
+                "threads".to_string(),
+                Value::Integer(4),
+            )])),
+        );
+
+        let mut entries = Vec::new();
+        flatten(Flavor::Properties, "mode", &mode, &mut entries).unwrap();
+        assert_eq!(
+            entries,
+            [("mode.Custom.threads".to_string(), "4".to_string())]
+        );
+    }
+}
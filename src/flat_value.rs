@@ -0,0 +1,119 @@
+// serde_config_docs/src/flat_value.rs
+//! Single values the way flat formats write them
+//!
+//! INI, `.properties` and `.env` files all hold one line of text per key, so
+//! they share how a value becomes that text: scalars as they are, and lists
+//! comma-separated.
+
+use crate::{Value, VariantData};
+
+/// The text of a single value in a flat format, where lists are written
+/// comma-separated
+///
+/// Tables and enum variants with data have no single-value form, so it's up
+/// to the format whether to spread those over several keys.
+pub(crate) fn flat_text(value: &Value) -> Result<String, &'static str> {
+    match value {
+        Value::Bool(b) => Ok(b.to_string()),
+        Value::Integer(i) => Ok(i.to_string()),
+        Value::Float(f) => Ok(f.to_string()),
+        Value::String(text) => Ok(text.clone()),
+        Value::Variant(name, data) if matches!(data.as_ref(), VariantData::Unit) => {
+            Ok(name.clone())
+        }
+        // An empty value reads back as an empty list or map
+        Value::Table(entries) | Value::Struct(_, entries) if entries.is_empty() => {
+            Ok(String::new())
+        }
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(flat_list_item)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(items.join(","))
+        }
+        Value::Null => Err("empty values can't be written as a flat value"),
+        Value::Table(_) | Value::Struct(..) => Err("maps can't be written as a single flat value"),
+        Value::Variant(..) => {
+            Err("enum variants with data can't be written as a single flat value")
+        }
+    }
+}
+
+/// The text of a list item, which has to survive being split on commas
+fn flat_list_item(item: &Value) -> Result<String, &'static str> {
+    match item {
+        Value::Table(_) | Value::Struct(..) => {
+            Err("arrays of tables can't be written as flat keys")
+        }
+        Value::Array(_) => Err("nested arrays can't be written as flat keys"),
+        Value::Null => Err("lists with empty items can't be written as flat keys"),
+        Value::Variant(..) => match flat_text(item) {
+            Ok(text) => Ok(text),
+            Err(_) => Err("enum variants with data can't be written inside a flat list"),
+        },
+        item => {
+            let text = flat_text(item)?;
+            if text.contains(',') || text.trim() != text {
+                Err(
+                    "list items can't contain commas or surrounding whitespace, \
+                     since lists are written comma-separated",
+                )
+            } else {
+                Ok(text)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    fn table(entries: &[(&str, Value)]) -> Value {
+        Value::Table(
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn flat_text_joins_scalar_lists() {
+        let list = Value::Array(vec![text("a"), Value::Integer(2), Value::Bool(true)]);
+        assert_eq!(flat_text(&list).unwrap(), "a,2,true");
+        assert_eq!(flat_text(&Value::Array(Vec::new())).unwrap(), "");
+    }
+
+    #[test]
+    fn flat_text_rejects_values_without_a_flat_form() {
+        let cases = [
+            (Value::Null, "empty values can't be written as a flat value"),
+            (
+                table(&[("a", Value::Integer(1))]),
+                "maps can't be written as a single flat value",
+            ),
+            (
+                Value::Array(vec![table(&[("a", Value::Integer(1))])]),
+                "arrays of tables can't be written as flat keys",
+            ),
+            (
+                Value::Array(vec![Value::Array(Vec::new())]),
+                "nested arrays can't be written as flat keys",
+            ),
+        ];
+        for (value, reason) in cases {
+            assert_eq!(flat_text(&value), Err(reason), "{:?}", value);
+        }
+
+        let with_comma = Value::Array(vec![text("a,b")]);
+        assert!(flat_text(&with_comma).is_err());
+        let padded = Value::Array(vec![text(" a")]);
+        assert!(flat_text(&padded).is_err());
+    }
+}
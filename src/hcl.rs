@@ -0,0 +1,555 @@
+// serde_config_docs/src/hcl.rs
+//! HashiCorp Configuration Language, as used by Terraform and Nomad
+//!
+//! Sections are written as blocks. Values that hold structs are blocks too,
+//! so a `Vec` of structs becomes repeated blocks and a map of structs becomes
+//! labeled blocks, e.g. `backend "s3" { ... }`. Everything else is an
+//! attribute, e.g. `port = 8080`.
+
+use crate::render::{
+    join_path, resolve_key, write_annotation, write_comment_lines, RenderContext, Renderer,
+};
+use crate::{ConfigFormat, ConfigSchema, DefaultsStyle, FieldInfo, ShapeError, Value, VariantData};
+
+use std::fmt::{self, Write};
+
+#[derive(Debug)]
+pub(crate) struct Hcl;
+
+impl Renderer for Hcl {
+    fn name(&self) -> &str {
+        "hcl"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["tf"]
+    }
+
+    fn extension(&self) -> &str {
+        "hcl"
+    }
+
+    fn comment_marker(&self) -> Option<&str> {
+        Some("#")
+    }
+
+    fn render_value(&self, value: &Value) -> String {
+        format_value(value)
+    }
+
+    fn render_document(&self, value: &Value) -> Result<String, ShapeError> {
+        Ok(format_document(value))
+    }
+
+    fn nests_sections(&self) -> bool {
+        true
+    }
+
+    fn validate(&self, fields: &[FieldInfo]) -> Result<(), ShapeError> {
+        validate(fields, "")
+    }
+
+    fn write_section(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        section: &FieldInfo,
+        _path: &[&str],
+    ) -> fmt::Result {
+        write_section(buffer, section, 0, ctx.defaults)
+    }
+
+    fn write_document(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        fields: &[FieldInfo],
+    ) -> fmt::Result {
+        write_body(buffer, fields, 0, ctx.defaults)
+    }
+
+    fn parse(&self, input: &str, _schema: Option<&ConfigSchema>) -> Result<Value, String> {
+        parse(input)
+    }
+}
+
+/// Check that every key that ends up in a block body is a valid identifier
+fn validate(fields: &[FieldInfo], path: &str) -> Result<(), ShapeError> {
+    for field in fields {
+        let current_path = join_path(path, &field.name);
+        check_identifier(&field.name, &current_path)?;
+
+        if field.is_nested {
+            validate(&field.nested_fields, &current_path)?;
+            continue;
+        }
+
+        for value in [&field.default_value, &field.example_value]
+            .into_iter()
+            .flatten()
+        {
+            validate_value(value, &current_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_value(value: &Value, path: &str) -> Result<(), ShapeError> {
+    for (_, entries) in as_blocks(value).unwrap_or_default() {
+        for (key, value) in entries {
+            let current_path = join_path(path, key);
+            check_identifier(key, &current_path)?;
+            validate_value(value, &current_path)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_identifier(name: &str, path: &str) -> Result<(), ShapeError> {
+    if is_identifier(name) {
+        return Ok(());
+    }
+
+    Err(ShapeError {
+        format: ConfigFormat::Hcl,
+        path: path.to_string(),
+        reason: "attribute and block names have to be identifiers".to_string(),
+    })
+}
+
+/// Write a nested section as a block
+fn write_section(
+    buffer: &mut String,
+    field: &FieldInfo,
+    indent: usize,
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    let pad = " ".repeat(indent);
+
+    writeln!(buffer, "{}{} {{", pad, field.name)?;
+    write_body(buffer, &field.nested_fields, indent + 2, defaults)?;
+    writeln!(buffer, "{}}}", pad)
+}
+
+/// Write the fields of a body, separated by blank lines
+fn write_body(
+    buffer: &mut String,
+    fields: &[FieldInfo],
+    indent: usize,
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    let pad = " ".repeat(indent);
+    let marker = format!("{}#", pad);
+
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            writeln!(buffer)?;
+        }
+
+        write_comment_lines(buffer, field.doc_comments.as_deref(), &marker)?;
+
+        if field.is_nested {
+            write_section(buffer, field, indent, defaults)?;
+            continue;
+        }
+
+        let key = resolve_key(field, defaults);
+        write_annotation(buffer, key.annotation.as_ref(), &marker, &Hcl)?;
+
+        let mut entry = String::new();
+        write_entry(&mut entry, &field.name, &key.value, indent)?;
+
+        if key.commented {
+            // Comment out every line of a block, keeping its indentation
+            for line in entry.lines() {
+                writeln!(buffer, "{}# {}", pad, &line[indent..])?;
+            }
+        } else {
+            buffer.push_str(&entry);
+        }
+    }
+
+    Ok(())
+}
+
+/// Write a value as an attribute, or as blocks if it holds structs
+fn write_entry(buffer: &mut String, name: &str, value: &Value, indent: usize) -> fmt::Result {
+    let pad = " ".repeat(indent);
+
+    let Some(blocks) = as_blocks(value) else {
+        return writeln!(buffer, "{}{} = {}", pad, name, format_value(value));
+    };
+
+    for (label, entries) in blocks {
+        let header = match label {
+            Some(label) => format!("{} {}", name, quote(label)),
+            None => name.to_string(),
+        };
+
+        // `None` fields are left out, the way serde leaves them out of TOML
+        let entries: Vec<_> = entries.iter().filter(|(_, v)| !v.is_null()).collect();
+        if entries.is_empty() {
+            writeln!(buffer, "{}{} {{}}", pad, header)?;
+            continue;
+        }
+
+        writeln!(buffer, "{}{} {{", pad, header)?;
+        for (key, value) in entries {
+            write_entry(buffer, key, value, indent + 2)?;
+        }
+        writeln!(buffer, "{}}}", pad)?;
+    }
+
+    Ok(())
+}
+
+/// A block's label, if it has one, and the entries of its body
+type Block<'a> = (Option<&'a str>, &'a [(String, Value)]);
+
+/// The bodies a value is written as, with their labels, or `None` if it's
+/// written as an attribute
+///
+/// A struct is one block, a list of structs is repeated blocks, and a map of
+/// structs is one labeled block per entry.
+fn as_blocks(value: &Value) -> Option<Vec<Block<'_>>> {
+    fn body(value: &Value) -> Option<&[(String, Value)]> {
+        match value {
+            Value::Struct(_, entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    match value {
+        Value::Struct(_, entries) => Some(vec![(None, entries)]),
+        Value::Array(items) if !items.is_empty() => items
+            .iter()
+            .map(|item| body(item).map(|entries| (None, entries)))
+            .collect(),
+        Value::Table(entries) if !entries.is_empty() => entries
+            .iter()
+            .map(|(label, item)| body(item).map(|entries| (Some(label.as_str()), entries)))
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Render a value as an HCL expression on a single line
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) if f.is_finite() => format!("{:?}", f),
+        // HCL numbers can't be infinite or NaN, but strings parse as floats
+        Value::Float(f) => quote(&f.to_string()),
+        Value::String(text) => quote(text),
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(format_value).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Table(entries) | Value::Struct(_, entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| format!("{} = {}", object_key(key), format_value(value)))
+                .collect();
+            if entries.is_empty() {
+                "{}".to_string()
+            } else {
+                format!("{{ {} }}", entries.join(", "))
+            }
+        }
+        Value::Variant(name, data) => match data.as_ref() {
+            VariantData::Unit => quote(name),
+            _ => format_value(&value.externally_tagged()),
+        },
+    }
+}
+
+/// Render a whole value as a body, with one attribute or block per key
+fn format_document(value: &Value) -> String {
+    let mut buffer = String::new();
+    match value {
+        Value::Table(entries) | Value::Struct(_, entries) => {
+            for (key, value) in entries.iter().filter(|(_, v)| !v.is_null()) {
+                write_entry(&mut buffer, key, value, 0).unwrap();
+            }
+        }
+        value => buffer.push_str(&format_value(value)),
+    }
+    buffer
+}
+
+/// An object key, quoted unless it's an identifier that can't be mistaken
+/// for a keyword
+fn object_key(key: &str) -> String {
+    if is_identifier(key) && !matches!(key, "true" | "false" | "null") {
+        key.to_string()
+    } else {
+        quote(key)
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Write a quoted HCL string, escaping template sequences
+///
+/// HCL strings are templates, so unlike KDL or INI strings `${` and `%{`
+/// have to be doubled to be read back literally.
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            // `${` and `%{` start interpolations unless doubled
+            '$' | '%' if chars.peek() == Some(&'{') => {
+                quoted.push(c);
+                quoted.push(c);
+            }
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Parse an HCL document into a value tree
+///
+/// Blocks are read the way the HCL JSON spec describes them, so a single
+/// repeated block comes back as an object rather than a list of one.
+fn parse(input: &str) -> Result<Value, String> {
+    ::hcl::from_str::<::hcl::Value>(input)
+        .map(from_hcl)
+        .map_err(|e| e.to_string())
+}
+
+fn from_hcl(value: ::hcl::Value) -> Value {
+    match value {
+        ::hcl::Value::Null => Value::Null,
+        ::hcl::Value::Bool(b) => Value::Bool(b),
+        ::hcl::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Float(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        ::hcl::Value::String(text) => Value::String(text),
+        ::hcl::Value::Array(items) => Value::Array(items.into_iter().map(from_hcl).collect()),
+        ::hcl::Value::Object(entries) => Value::Table(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key, from_hcl(value)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_example, ExampleOptions};
+
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    fn listener(port: i64) -> Value {
+        Value::Struct(
+            "Listener".to_string(),
+            vec![("port".to_string(), Value::Integer(port))],
+        )
+    }
+
+    fn fields() -> Vec<FieldInfo> {
+        vec![
+            FieldInfo::new("name")
+                .doc("Name shown in logs")
+                .field_type("String")
+                .default(Value::from("app-${env}")),
+            FieldInfo::new("listeners")
+                .field_type("Vec<Listener>")
+                .default(Value::Array(vec![listener(80), listener(443)])),
+            FieldInfo::new("backends")
+                .field_type("HashMap<String, Backend>")
+                .default(Value::Table(vec![(
+                    "s3".to_string(),
+                    Value::Struct(
+                        "Backend".to_string(),
+                        vec![
+                            ("bucket".to_string(), Value::from("logs")),
+                            ("region".to_string(), Value::Null),
+                        ],
+                    ),
+                )])),
+            FieldInfo::new("server").nested(vec![
+                FieldInfo::new("host")
+                    .field_type("String")
+                    .default(Value::from("localhost")),
+                FieldInfo::new("timeout")
+                    .field_type("Option<u64>")
+                    .example(Value::Integer(30)),
+            ]),
+        ]
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Config {
+        name: String,
+        listeners: Vec<Listener>,
+        backends: BTreeMap<String, Backend>,
+        server: Server,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Listener {
+        port: u16,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Backend {
+        bucket: String,
+        region: Option<String>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Server {
+        host: String,
+        timeout: Option<u64>,
+    }
+
+    #[test]
+    fn structs_are_written_as_blocks() {
+        let example = generate_example(&fields(), &ExampleOptions::new(ConfigFormat::Hcl));
+        assert_eq!(
+            example,
+            r#"# Name shown in logs
+# Default: "app-$${env}"
+name = "app-$${env}"
+
+# Default: [{ port = 80 }, { port = 443 }]
+listeners {
+  port = 80
+}
+listeners {
+  port = 443
+}
+
+# Default: { s3 = { bucket = "logs" } }
+backends "s3" {
+  bucket = "logs"
+}
+
+server {
+  # Default: "localhost"
+  host = "localhost"
+
+  # Optional
+  timeout = 30
+}
+"#
+        );
+
+        let config: Config = ConfigFormat::Hcl.parse(&example).unwrap();
+        assert_eq!(config.name, "app-${env}");
+        assert_eq!(
+            config.listeners,
+            [Listener { port: 80 }, Listener { port: 443 }]
+        );
+        assert_eq!(
+            config.backends["s3"],
+            Backend {
+                bucket: "logs".to_string(),
+                region: None,
+            }
+        );
+        assert_eq!(config.server.timeout, Some(30));
+    }
+
+    #[test]
+    fn commented_out_blocks_keep_their_indentation() {
+        let mut options = ExampleOptions::new(ConfigFormat::Hcl);
+        options.defaults = DefaultsStyle::CommentedOut;
+        let example = generate_example(&fields()[1..], &options);
+        // Example values stay active
+        assert_eq!(
+            example,
+            r#"# listeners {
+#   port = 80
+# }
+# listeners {
+#   port = 443
+# }
+
+# backends "s3" {
+#   bucket = "logs"
+# }
+
+server {
+  # host = "localhost"
+
+  # Optional
+  timeout = 30
+}
+"#
+        );
+    }
+
+    #[test]
+    fn strings_are_read_back_literally() {
+        for text in [
+            "${var}",
+            "%{ if x }",
+            "$${kept}",
+            "50% {",
+            "a\"b\\c\nd\te",
+            "bell\u{7}",
+        ] {
+            let document = format!("value = {}\n", quote(text));
+            let value = parse(&document).unwrap();
+            assert_eq!(
+                value,
+                Value::Table(vec![("value".to_string(), Value::from(text))]),
+                "{}",
+                document
+            );
+        }
+    }
+
+    #[test]
+    fn non_identifier_keys_are_a_shape_error() {
+        let fields = [FieldInfo::new("server").nested(vec![FieldInfo::new("2fa")])];
+        let err = Hcl.validate(&fields).unwrap_err();
+        assert_eq!(err.path, "server.2fa");
+
+        // Keys inside block values are checked too
+        let fields = [FieldInfo::new("backends").default(Value::Table(vec![(
+            "s3".to_string(),
+            Value::Struct(
+                "Backend".to_string(),
+                vec![("bucket name".to_string(), Value::from("logs"))],
+            ),
+        )]))];
+        let err = Hcl.validate(&fields).unwrap_err();
+        assert_eq!(err.path, "backends.bucket name");
+
+        // Object keys in attributes are quoted instead
+        assert_eq!(
+            format_value(&Value::Table(vec![
+                ("bucket name".to_string(), Value::Integer(1)),
+                ("null".to_string(), Value::Integer(2)),
+            ])),
+            r#"{ "bucket name" = 1, "null" = 2 }"#
+        );
+    }
+}
@@ -0,0 +1,285 @@
+// serde_config_docs/src/inject.rs
+//! Keep generated docs up to date inside hand-written Markdown files
+//!
+//! Docs are written between a pair of HTML comments, which Markdown renders
+//! as nothing:
+//!
+//! ```markdown
+//! <!-- config-docs:start Config -->
+//! ...generated docs...
+//! <!-- config-docs:end -->
+//! ```
+//!
+//! Everything outside the markers is left as it is, and each region is
+//! replaced whole, so injecting the same docs twice changes nothing.
+
+use crate::{ConfigDocsStruct, MarkdownOptions, ShapeError};
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Replace the regions marked for `name` in a Markdown document with `docs`
+///
+/// Every region for `name` is replaced, while regions for other names are
+/// left alone. Markers have to be on lines of their own, and regions can't
+/// be nested.
+pub fn inject_docs(document: &str, name: &str, docs: &str) -> Result<String, InjectError> {
+    let mut output = String::with_capacity(document.len() + docs.len());
+    // The name and line number of the region being read, if any
+    let mut region: Option<(&str, usize)> = None;
+    let mut found = false;
+
+    for (index, line) in document.split_inclusive('\n').enumerate() {
+        let line_number = index + 1;
+
+        match (parse_marker(line), region) {
+            (Some(Marker::Start(_)), Some((open, start))) => {
+                return Err(InjectError::Unbalanced {
+                    line: line_number,
+                    message: format!(
+                        "start marker inside the region for `{}` started on line {}",
+                        open, start
+                    ),
+                });
+            }
+            (Some(Marker::Start(marker_name)), None) => {
+                region = Some((marker_name, line_number));
+                output.push_str(line);
+            }
+            (Some(Marker::End), None) => {
+                return Err(InjectError::Unbalanced {
+                    line: line_number,
+                    message: "end marker without a start marker".to_string(),
+                });
+            }
+            (Some(Marker::End), Some((open, _))) => {
+                region = None;
+                if open == name {
+                    found = true;
+                    // Blank lines keep the docs apart from the markers
+                    output.push('\n');
+                    output.push_str(docs.trim());
+                    output.push_str("\n\n");
+                }
+                output.push_str(line);
+            }
+            (None, Some((open, _))) if open == name => {}
+            (None, _) => output.push_str(line),
+        }
+    }
+
+    if let Some((open, start)) = region {
+        return Err(InjectError::MissingEnd {
+            name: open.to_string(),
+            line: start,
+        });
+    }
+    if !found {
+        return Err(InjectError::MissingStart {
+            name: name.to_string(),
+        });
+    }
+
+    Ok(output)
+}
+
+/// Replace the regions marked for `name` in a Markdown file with `docs`
+///
+/// The file is only written if all of its markers are in order.
+pub fn inject_docs_into_file(
+    path: impl AsRef<Path>,
+    name: &str,
+    docs: &str,
+) -> Result<(), InjectError> {
+    let path = path.as_ref();
+    let document = fs::read_to_string(path).map_err(InjectError::Io)?;
+    let injected = inject_docs(&document, name, docs)?;
+
+    if injected != document {
+        fs::write(path, injected).map_err(InjectError::Io)?;
+    }
+    Ok(())
+}
+
+/// Generate docs for a type that implements ConfigDocsStruct and inject them
+/// into the regions marked with the type's name in a Markdown file
+pub fn inject_config_docs<T: ConfigDocsStruct>(
+    path: impl AsRef<Path>,
+    options: &MarkdownOptions,
+) -> Result<(), InjectError> {
+    let schema = T::schema();
+    let name = schema.name.as_deref().unwrap_or_default();
+    let docs = schema
+        .try_generate_docs_with_options(options)
+        .map_err(InjectError::Shape)?;
+    inject_docs_into_file(path, name, &docs)
+}
+
+/// A `config-docs` marker comment
+enum Marker<'a> {
+    Start(&'a str),
+    End,
+}
+
+/// Parse a line holding a marker comment, e.g. `<!-- config-docs:start Config -->`
+fn parse_marker(line: &str) -> Option<Marker<'_>> {
+    let comment = line
+        .trim()
+        .strip_prefix("<!--")?
+        .strip_suffix("-->")?
+        .trim();
+
+    if let Some(name) = comment.strip_prefix("config-docs:start") {
+        // The name has to be apart from the keyword, e.g. not `startConfig`
+        if name.starts_with(char::is_whitespace) || name.is_empty() {
+            return Some(Marker::Start(name.trim()));
+        }
+    } else if comment == "config-docs:end" {
+        return Some(Marker::End);
+    }
+
+    None
+}
+
+/// Error returned when docs can't be injected into a Markdown file
+#[derive(Debug)]
+pub enum InjectError {
+    /// The document has no region for the name
+    MissingStart { name: String },
+    /// The region started on `line` is never closed
+    MissingEnd { name: String, line: usize },
+    /// A marker on `line` doesn't pair up with the others
+    Unbalanced { line: usize, message: String },
+    /// The docs can't be written in the chosen format
+    Shape(ShapeError),
+    /// The file couldn't be read or written
+    Io(io::Error),
+}
+
+impl fmt::Display for InjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InjectError::MissingStart { name } => write!(
+                f,
+                "no `<!-- config-docs:start {} -->` marker to inject docs after",
+                name
+            ),
+            InjectError::MissingEnd { name, line } => write!(
+                f,
+                "the region for `{}` started on line {} has no `<!-- config-docs:end -->` marker",
+                name, line
+            ),
+            InjectError::Unbalanced { line, message } => write!(f, "line {}: {}", line, message),
+            InjectError::Shape(err) => write!(f, "{}", err),
+            InjectError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for InjectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InjectError::Shape(err) => Some(err),
+            InjectError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = "\
+# Settings
+
+<!-- config-docs:start Config -->
+old docs
+<!-- config-docs:end -->
+
+Hand-written text.
+";
+
+    #[test]
+    fn replaces_the_region_and_keeps_the_rest() {
+        let injected = inject_docs(DOCUMENT, "Config", "new docs\n").unwrap();
+        assert_eq!(
+            injected,
+            "\
+# Settings
+
+<!-- config-docs:start Config -->
+
+new docs
+
+<!-- config-docs:end -->
+
+Hand-written text.
+"
+        );
+    }
+
+    #[test]
+    fn injecting_twice_changes_nothing() {
+        let once = inject_docs(DOCUMENT, "Config", "new docs").unwrap();
+        let twice = inject_docs(&once, "Config", "new docs").unwrap();
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn every_region_for_the_name_is_replaced() {
+        let document = "\
+<!-- config-docs:start Config -->
+a
+<!-- config-docs:end -->
+<!-- config-docs:start Other -->
+b
+<!-- config-docs:end -->
+<!-- config-docs:start Config -->
+c
+<!-- config-docs:end -->
+";
+        let injected = inject_docs(document, "Config", "docs").unwrap();
+        assert_eq!(injected.matches("\ndocs\n").count(), 2);
+        assert!(!injected.contains("\na\n") && !injected.contains("\nc\n"));
+        assert!(injected.contains("\nb\n"));
+    }
+
+    #[test]
+    fn missing_start_is_an_error() {
+        let err = inject_docs(DOCUMENT, "Other", "docs").unwrap_err();
+        assert!(matches!(err, InjectError::MissingStart { name } if name == "Other"));
+
+        // The keyword has to be apart from the name
+        let document = "<!-- config-docs:startConfig -->\n<!-- config-docs:end -->\n";
+        let err = inject_docs(document, "Config", "docs").unwrap_err();
+        assert!(matches!(err, InjectError::Unbalanced { line: 2, .. }));
+    }
+
+    #[test]
+    fn missing_end_is_an_error() {
+        let document = "intro\n<!-- config-docs:start Config -->\nold docs\n";
+        let err = inject_docs(document, "Config", "docs").unwrap_err();
+        assert!(matches!(err, InjectError::MissingEnd { name, line: 2 } if name == "Config"));
+    }
+
+    #[test]
+    fn nested_regions_are_an_error() {
+        let document = "\
+<!-- config-docs:start Config -->
+<!-- config-docs:start Other -->
+<!-- config-docs:end -->
+<!-- config-docs:end -->
+";
+        let err = inject_docs(document, "Config", "docs").unwrap_err();
+        match err {
+            InjectError::Unbalanced { line, message } => {
+                assert_eq!(line, 2);
+                assert!(message.contains("started on line 1"), "{}", message);
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+}
@@ -0,0 +1,216 @@
+// serde_config_docs/src/json.rs
+//! Plain JSON
+//!
+//! JSON has no comments, so example configs hold only the active values, and
+//! Markdown docs add a reference table of each section's keys.
+
+use crate::render::{resolve_key, visit_deserializer, RenderContext, Renderer, Visit};
+use crate::{ConfigFormat, ConfigSchema, DefaultsStyle, FieldInfo, ShapeError, Value};
+
+use std::fmt::{self, Write};
+
+#[derive(Debug)]
+pub(crate) struct Json;
+
+impl Renderer for Json {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn extension(&self) -> &str {
+        "json"
+    }
+
+    fn comment_marker(&self) -> Option<&str> {
+        None
+    }
+
+    fn render_value(&self, value: &Value) -> String {
+        serde_json::to_string(value).unwrap()
+    }
+
+    fn render_document(&self, value: &Value) -> Result<String, ShapeError> {
+        serde_json::to_string_pretty(value).map_err(|err| ShapeError {
+            format: ConfigFormat::Json,
+            path: String::new(),
+            reason: err.to_string(),
+        })
+    }
+
+    fn write_section(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        section: &FieldInfo,
+        _path: &[&str],
+    ) -> fmt::Result {
+        let section = Value::Table(vec![(
+            section.name.clone(),
+            example_tree(&leaf_fields(section), ctx.defaults),
+        )]);
+        let section = self.render_document(&section).map_err(|_| fmt::Error)?;
+        writeln!(buffer, "{}", section)
+    }
+
+    fn write_document(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        fields: &[FieldInfo],
+    ) -> fmt::Result {
+        let tree = example_tree(fields, ctx.defaults);
+        buffer.push_str(&self.render_document(&tree).map_err(|_| fmt::Error)?);
+        Ok(())
+    }
+
+    fn deserialize(
+        &self,
+        input: &str,
+        _schema: Option<&ConfigSchema>,
+        visit: &mut Visit<'_>,
+    ) -> Result<(), String> {
+        deserialize_json(input, visit)
+    }
+}
+
+/// Deserialize a JSON document, rejecting anything after it
+fn deserialize_json(input: &str, visit: &mut Visit<'_>) -> Result<(), String> {
+    let mut deserializer = serde_json::Deserializer::from_str(input);
+    visit_deserializer(visit, &mut deserializer)?;
+    deserializer.end().map_err(|e| e.to_string())
+}
+
+/// The fields of a section that aren't sections themselves
+fn leaf_fields(section: &FieldInfo) -> Vec<FieldInfo> {
+    section
+        .nested_fields
+        .iter()
+        .filter(|f| !f.is_nested)
+        .cloned()
+        .collect()
+}
+
+/// Build the active values of an example as a single tree
+fn example_tree(fields: &[FieldInfo], defaults: DefaultsStyle) -> Value {
+    let mut entries = Vec::new();
+
+    for field in fields {
+        if field.is_nested {
+            entries.push((
+                field.name.clone(),
+                example_tree(&field.nested_fields, defaults),
+            ));
+            continue;
+        }
+
+        let key = resolve_key(field, defaults);
+        if !key.commented {
+            entries.push((field.name.clone(), key.value));
+        }
+    }
+
+    Value::Table(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        generate_example, generate_markdown, ConfigFormat, DefaultsStyle, ExampleOptions,
+        FieldInfo, MarkdownOptions, Value, VariantData,
+    };
+
+    use serde::Deserialize;
+
+    fn fields() -> Vec<FieldInfo> {
+        vec![
+            FieldInfo::new("name").field_type("String").required(true),
+            FieldInfo::new("port")
+                .doc("Port")
+                .field_type("u16")
+                .default(Value::Integer(8080)),
+            FieldInfo::new("mode")
+                .field_type("Mode")
+                .default(Value::Variant(
+                    "Tls".to_string(),
+                    Box::new(VariantData::Newtype(Value::from("cert.pem"))),
+                )),
+            FieldInfo::new("server").nested(vec![FieldInfo::new("host")
+                .field_type("String")
+                .default(Value::from("localhost"))]),
+        ]
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Config {
+        name: String,
+        #[serde(default)]
+        port: u16,
+        mode: Option<Mode>,
+        server: Server,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Mode {
+        Tls(String),
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Server {
+        #[serde(default)]
+        host: String,
+    }
+
+    #[test]
+    fn example_holds_only_values() {
+        let options = ExampleOptions::new(ConfigFormat::Json);
+        let example = generate_example(&fields(), &options);
+        assert_eq!(
+            example,
+            r#"{
+  "name": "",
+  "port": 8080,
+  "mode": {
+    "Tls": "cert.pem"
+  },
+  "server": {
+    "host": "localhost"
+  }
+}
+"#
+        );
+
+        let config: Config = ConfigFormat::Json.parse(&example).unwrap();
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.mode, Some(Mode::Tls("cert.pem".to_string())));
+        assert_eq!(config.server.host, "localhost");
+    }
+
+    #[test]
+    fn commented_out_defaults_are_left_out() {
+        let options = ExampleOptions::new(ConfigFormat::Json).defaults(DefaultsStyle::CommentedOut);
+        let example = generate_example(&fields(), &options);
+        assert_eq!(example, "{\n  \"name\": \"\",\n  \"server\": {}\n}\n");
+
+        let config: Config = ConfigFormat::Json.parse(&example).unwrap();
+        assert_eq!(config.port, 0);
+        assert_eq!(config.mode, None);
+    }
+
+    #[test]
+    fn markdown_docs_add_a_reference_table() {
+        let docs = generate_markdown(&fields(), &MarkdownOptions::new(ConfigFormat::Json));
+        assert!(
+            docs.contains("| `port` | `u16` | `8080` | `PORT` | Port |"),
+            "{}",
+            docs
+        );
+    }
+
+    #[test]
+    fn anything_after_the_document_is_an_error() {
+        let err = ConfigFormat::Json
+            .parse::<Server>("{\"host\": \"a\"} {}")
+            .unwrap_err();
+        assert!(err.contains("trailing characters"), "{}", err);
+    }
+}
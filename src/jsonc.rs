@@ -0,0 +1,289 @@
+// serde_config_docs/src/jsonc.rs
+//! JSON with comments, as read by VS Code and many frontend tools
+//!
+//! Doc comments are written as `//` lines above each key, and every entry
+//! ends in a comma so keys can be commented out without breaking the
+//! surrounding object.
+
+use crate::render::{
+    resolve_key, visit_deserializer, write_annotation, write_comment_lines, RenderContext,
+    Renderer, Visit,
+};
+use crate::{ConfigFormat, ConfigSchema, DefaultsStyle, FieldInfo, ShapeError, Value};
+
+use std::fmt::{self, Write};
+
+#[derive(Debug)]
+pub(crate) struct Jsonc;
+
+impl Renderer for Jsonc {
+    fn name(&self) -> &str {
+        "jsonc"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["json5"]
+    }
+
+    fn extension(&self) -> &str {
+        "jsonc"
+    }
+
+    fn comment_marker(&self) -> Option<&str> {
+        Some("//")
+    }
+
+    fn render_value(&self, value: &Value) -> String {
+        serde_json::to_string(value).unwrap()
+    }
+
+    fn render_document(&self, value: &Value) -> Result<String, ShapeError> {
+        serde_json::to_string_pretty(value).map_err(|err| ShapeError {
+            format: ConfigFormat::Jsonc,
+            path: String::new(),
+            reason: err.to_string(),
+        })
+    }
+
+    fn nests_sections(&self) -> bool {
+        true
+    }
+
+    /// Write a document holding a single section, with its nested sections
+    /// inside
+    fn write_section(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        section: &FieldInfo,
+        _path: &[&str],
+    ) -> fmt::Result {
+        writeln!(buffer, "{{")?;
+        write_section(buffer, section, 2, ctx.defaults)?;
+        writeln!(buffer, "}}")
+    }
+
+    fn write_document(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        fields: &[FieldInfo],
+    ) -> fmt::Result {
+        writeln!(buffer, "{{")?;
+        write_entries(buffer, fields, 2, ctx.defaults)?;
+        writeln!(buffer, "}}")
+    }
+
+    fn deserialize(
+        &self,
+        input: &str,
+        _schema: Option<&ConfigSchema>,
+        visit: &mut Visit<'_>,
+    ) -> Result<(), String> {
+        let json = to_json(input);
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        visit_deserializer(visit, &mut deserializer)?;
+        deserializer.end().map_err(|e| e.to_string())
+    }
+}
+
+/// Write the entries of an object, separated by blank lines
+fn write_entries(
+    buffer: &mut String,
+    fields: &[FieldInfo],
+    indent: usize,
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    let pad = " ".repeat(indent);
+    let marker = format!("{}//", pad);
+
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            writeln!(buffer)?;
+        }
+
+        write_comment_lines(buffer, field.doc_comments.as_deref(), &marker)?;
+
+        if field.is_nested {
+            write_section(buffer, field, indent, defaults)?;
+            continue;
+        }
+
+        let key = resolve_key(field, defaults);
+        write_annotation(buffer, key.annotation.as_ref(), &marker, &Jsonc)?;
+
+        let name = Jsonc.render_value(&Value::String(field.name.clone()));
+        let value = Jsonc.render_value(&key.value);
+        if key.commented {
+            writeln!(buffer, "{}// {}: {},", pad, name, value)?;
+        } else {
+            writeln!(buffer, "{}{}: {},", pad, name, value)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Write a nested section as a key holding an object
+fn write_section(
+    buffer: &mut String,
+    field: &FieldInfo,
+    indent: usize,
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    let pad = " ".repeat(indent);
+    let name = Jsonc.render_value(&Value::String(field.name.clone()));
+
+    writeln!(buffer, "{}{}: {{", pad, name)?;
+    write_entries(buffer, &field.nested_fields, indent + 2, defaults)?;
+    writeln!(buffer, "{}}},", pad)
+}
+
+/// Strip comments and trailing commas, leaving plain JSON
+///
+/// This keeps string contents intact, including `//` inside URLs.
+fn to_json(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                output.push(c);
+                while let Some(c) = chars.next() {
+                    output.push(c);
+                    match c {
+                        '\\' => output.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = None;
+                for c in chars.by_ref() {
+                    if previous == Some('*') && c == '/' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+            }
+            ']' | '}' => {
+                // Drop a trailing comma before the closing bracket
+                let content_end = output.trim_end().len();
+                if output[..content_end].ends_with(',') {
+                    output.remove(content_end - 1);
+                }
+                output.push(c);
+            }
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_example, ConfigFormat, ExampleOptions};
+
+    use serde::Deserialize;
+
+    fn fields() -> Vec<FieldInfo> {
+        vec![
+            FieldInfo::new("port")
+                .doc("Port")
+                .field_type("u16")
+                .default(Value::Integer(8080)),
+            FieldInfo::new("server").doc("Server settings").nested(vec![
+                FieldInfo::new("host")
+                    .field_type("String")
+                    .default(Value::from("localhost")),
+                FieldInfo::new("timeout").field_type("Option<u64>"),
+            ]),
+        ]
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Config {
+        #[serde(default)]
+        port: u16,
+        server: Server,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Server {
+        #[serde(default)]
+        host: String,
+        timeout: Option<u64>,
+    }
+
+    #[test]
+    fn example_has_comments_and_trailing_commas() {
+        let example = generate_example(&fields(), &ExampleOptions::new(ConfigFormat::Jsonc));
+        assert_eq!(
+            example,
+            r#"{
+  // Port
+  // Default: 8080
+  "port": 8080,
+
+  // Server settings
+  "server": {
+    // Default: "localhost"
+    "host": "localhost",
+
+    // Optional
+    // "timeout": 0,
+  },
+}
+"#
+        );
+
+        let config: Config = ConfigFormat::Jsonc.parse(&example).unwrap();
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.server.host, "localhost");
+        assert_eq!(config.server.timeout, None);
+    }
+
+    #[test]
+    fn commented_out_defaults_still_parse() {
+        let options =
+            ExampleOptions::new(ConfigFormat::Jsonc).defaults(DefaultsStyle::CommentedOut);
+        let example = generate_example(&fields(), &options);
+        assert!(example.contains("  // \"port\": 8080,\n"), "{}", example);
+
+        let config: Config = ConfigFormat::Jsonc.parse(&example).unwrap();
+        assert_eq!(config.port, 0);
+    }
+
+    #[test]
+    fn comments_and_trailing_commas_are_stripped() {
+        let input = r#"{
+  // a comment
+  "url": "https://example.com", /* inline */
+  "quote": "say \"//hi\"",
+  "list": [1, 2, ],
+  /* a block
+     over lines */
+  "nested": { "a": 1, },
+}"#;
+        let value: serde_json::Value = serde_json::from_str(&to_json(input)).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "url": "https://example.com",
+                "quote": "say \"//hi\"",
+                "list": [1, 2],
+                "nested": { "a": 1 },
+            })
+        );
+    }
+}
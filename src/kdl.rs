@@ -0,0 +1,605 @@
+// serde_config_docs/src/kdl.rs
+//! KDL documents, following version 2 of the spec
+//!
+//! Sections are written as nodes with children, and each leaf field as a
+//! child node with a single argument, e.g. `port 8080`. Keys that are left
+//! out of the config are commented out with a slashdash, e.g. `/-port 8080`.
+//!
+//! Generated examples are read back with a small parser for exactly what the
+//! renderer writes: nodes with a single argument or a children block, quoted
+//! strings with its escapes, decimal numbers, `#` keywords, `//` comment
+//! lines and slashdashed nodes. Anything else, such as
+//! properties, several arguments, raw strings, block comments or type
+//! annotations, is an error. This is enough to check examples without
+//! pulling in the `kdl` crate and its dependencies, but it isn't a KDL reader
+//! for hand-written files.
+
+use crate::render::{resolve_key, write_annotation, write_comment_lines, RenderContext, Renderer};
+use crate::{ConfigSchema, DefaultsStyle, FieldInfo, ShapeError, Value, VariantData};
+
+use std::fmt::{self, Write};
+
+#[derive(Debug)]
+pub(crate) struct Kdl;
+
+impl Renderer for Kdl {
+    fn name(&self) -> &str {
+        "kdl"
+    }
+
+    fn extension(&self) -> &str {
+        "kdl"
+    }
+
+    fn comment_marker(&self) -> Option<&str> {
+        Some("//")
+    }
+
+    fn render_value(&self, value: &Value) -> String {
+        format_value(value)
+    }
+
+    fn render_document(&self, value: &Value) -> Result<String, ShapeError> {
+        Ok(format_document(value))
+    }
+
+    fn nests_sections(&self) -> bool {
+        true
+    }
+
+    fn write_section(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        section: &FieldInfo,
+        _path: &[&str],
+    ) -> fmt::Result {
+        write_section(buffer, section, 0, ctx.defaults)
+    }
+
+    fn write_document(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        fields: &[FieldInfo],
+    ) -> fmt::Result {
+        write_nodes(buffer, fields, 0, ctx.defaults)
+    }
+
+    fn parse(&self, input: &str, _schema: Option<&ConfigSchema>) -> Result<Value, String> {
+        parse(input)
+    }
+}
+
+/// Write a nested section as a node with one child per field
+fn write_section(
+    buffer: &mut String,
+    field: &FieldInfo,
+    indent: usize,
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    let pad = " ".repeat(indent);
+
+    writeln!(buffer, "{}{} {{", pad, node_name(&field.name))?;
+    write_nodes(buffer, &field.nested_fields, indent + 4, defaults)?;
+    writeln!(buffer, "{}}}", pad)
+}
+
+/// Write a node for each field, separated by blank lines
+fn write_nodes(
+    buffer: &mut String,
+    fields: &[FieldInfo],
+    indent: usize,
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    let pad = " ".repeat(indent);
+    let marker = format!("{}//", pad);
+
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            writeln!(buffer)?;
+        }
+
+        write_comment_lines(buffer, field.doc_comments.as_deref(), &marker)?;
+
+        if field.is_nested {
+            write_section(buffer, field, indent, defaults)?;
+            continue;
+        }
+
+        let key = resolve_key(field, defaults);
+        write_annotation(buffer, key.annotation.as_ref(), &marker, &Kdl)?;
+
+        let node = format!("{} {}", node_name(&field.name), format_value(&key.value));
+        if key.commented {
+            writeln!(buffer, "{}/-{}", pad, node)?;
+        } else {
+            writeln!(buffer, "{}{}", pad, node)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Render a value as what follows a node name, on a single line
+///
+/// Scalars are a single argument. Lists become `-` child nodes and tables
+/// become named child nodes, e.g. `{ - 1; - 2 }`.
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => "#null".to_string(),
+        Value::Bool(b) => format!("#{}", b),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) if f.is_nan() => "#nan".to_string(),
+        Value::Float(f) if f.is_infinite() && *f > 0.0 => "#inf".to_string(),
+        Value::Float(f) if f.is_infinite() => "#-inf".to_string(),
+        Value::Float(f) => format!("{:?}", f),
+        Value::String(text) => quote(text),
+        Value::Array(items) if items.is_empty() => "{}".to_string(),
+        Value::Array(items) => {
+            let items: Vec<String> = items
+                .iter()
+                .map(|item| format!("- {}", format_value(item)))
+                .collect();
+            format!("{{ {} }}", items.join("; "))
+        }
+        Value::Table(entries) | Value::Struct(_, entries) if entries.is_empty() => "{}".to_string(),
+        Value::Table(entries) | Value::Struct(_, entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| format!("{} {}", node_name(key), format_value(value)))
+                .collect();
+            format!("{{ {} }}", entries.join("; "))
+        }
+        Value::Variant(name, data) => match data.as_ref() {
+            VariantData::Unit => quote(name),
+            _ => format_value(&value.externally_tagged()),
+        },
+    }
+}
+
+/// Render a whole value as a document, with one node per top-level key
+fn format_document(value: &Value) -> String {
+    match value {
+        Value::Table(entries) | Value::Struct(_, entries) => entries
+            .iter()
+            .map(|(key, value)| format!("{} {}\n", node_name(key), format_value(value)))
+            .collect(),
+        value => format!("- {}\n", format_value(value)),
+    }
+}
+
+/// A node name, quoted unless it's a valid bare identifier
+fn node_name(name: &str) -> String {
+    let is_bare = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        && !matches!(name, "true" | "false" | "null" | "inf" | "nan");
+
+    if is_bare {
+        name.to_string()
+    } else {
+        quote(name)
+    }
+}
+
+/// Write a quoted KDL string
+///
+/// KDL writes other control characters as `\u{..}` with braces, unlike the
+/// fixed-width escapes of HCL, and has `\b` and `\f` as well.
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '\x08' => quoted.push_str("\\b"),
+            '\x0c' => quoted.push_str("\\f"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// A parsed KDL node
+struct Node {
+    name: String,
+    value: Value,
+}
+
+/// Parse a KDL document into a table with one entry per top-level node
+fn parse(input: &str) -> Result<Value, String> {
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        pos: 0,
+    };
+
+    let nodes = parser
+        .parse_nodes(false)
+        .map_err(|message| format!("line {}: {}", parser.line(), message))?;
+    children_value(nodes)
+}
+
+/// The value of a children block: a list if every child is a `-` node, and
+/// a table otherwise
+fn children_value(children: Vec<Node>) -> Result<Value, String> {
+    if !children.is_empty() && children.iter().all(|child| child.name == "-") {
+        return Ok(Value::Array(
+            children.into_iter().map(|child| child.value).collect(),
+        ));
+    }
+
+    let mut entries: Vec<(String, Value)> = Vec::new();
+    for child in children {
+        if entries.iter().any(|(name, _)| *name == child.name) {
+            return Err(format!("`{}` appears more than once", child.name));
+        }
+        entries.push((child.name, child.value));
+    }
+    Ok(Value::Table(entries))
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn starts_with(&self, text: &str) -> bool {
+        text.chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    /// The line the parser stopped at, for error messages
+    fn line(&self) -> usize {
+        self.chars[..self.pos.min(self.chars.len())]
+            .iter()
+            .filter(|&&c| c == '\n')
+            .count()
+            + 1
+    }
+
+    /// Parse nodes until the end of the input, or the closing brace of a
+    /// children block
+    fn parse_nodes(&mut self, in_block: bool) -> Result<Vec<Node>, String> {
+        let mut nodes = Vec::new();
+
+        loop {
+            self.skip_space(true);
+
+            match self.peek() {
+                None if in_block => return Err("expected `}`".to_string()),
+                None => return Ok(nodes),
+                Some('}') if in_block => {
+                    self.pos += 1;
+                    return Ok(nodes);
+                }
+                Some('}') => return Err("unexpected `}`".to_string()),
+                Some(';') => self.pos += 1,
+                _ if self.starts_with("/-") => {
+                    self.pos += 2;
+                    self.parse_node()?;
+                }
+                _ => nodes.push(self.parse_node()?),
+            }
+        }
+    }
+
+    /// Parse a node name followed by a single argument or a children block
+    fn parse_node(&mut self) -> Result<Node, String> {
+        let name = match self.peek() {
+            Some('"') => self.parse_string()?,
+            _ => self.parse_bare(),
+        };
+        if name.is_empty() {
+            return Err(self.unexpected("a node name"));
+        }
+
+        self.skip_space(false);
+        let value = match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                children_value(self.parse_nodes(true)?)?
+            }
+            None | Some('\n' | ';' | '}') => return Err(format!("`{}` has no value", name)),
+            _ => self.parse_value()?,
+        };
+
+        self.skip_space(false);
+        match self.peek() {
+            None | Some('\n' | ';' | '}') => Ok(Node { name, value }),
+            _ => Err(format!("`{}` has more than one value", name)),
+        }
+    }
+
+    /// Skip spaces, and line breaks and comment lines too if `newlines` is set
+    fn skip_space(&mut self, newlines: bool) {
+        loop {
+            match self.peek() {
+                Some(' ' | '\t' | '\r') => self.pos += 1,
+                Some('\n') if newlines => self.pos += 1,
+                _ if newlines && self.starts_with("//") => {
+                    while !matches!(self.peek(), None | Some('\n')) {
+                        self.pos += 1;
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Parse a quoted string, number or keyword
+    fn parse_value(&mut self) -> Result<Value, String> {
+        match self.peek() {
+            Some('"') => self.parse_string().map(Value::String),
+            Some('#') => {
+                self.pos += 1;
+                let keyword = self.parse_bare();
+                match keyword.as_str() {
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    "null" => Ok(Value::Null),
+                    "inf" => Ok(Value::Float(f64::INFINITY)),
+                    "-inf" => Ok(Value::Float(f64::NEG_INFINITY)),
+                    "nan" => Ok(Value::Float(f64::NAN)),
+                    _ => Err(format!("unknown keyword `#{}`", keyword)),
+                }
+            }
+            _ => {
+                let start = self.pos;
+                let number = self.parse_bare();
+                match number.parse::<i64>() {
+                    Ok(i) => Ok(Value::Integer(i)),
+                    Err(_) => number.parse::<f64>().map(Value::Float).map_err(|_| {
+                        self.pos = start;
+                        self.unexpected("a value")
+                    }),
+                }
+            }
+        }
+    }
+
+    fn parse_bare(&mut self) -> String {
+        let mut bare = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || "\\/(){};[]\"#=".contains(c) {
+                break;
+            }
+            bare.push(c);
+            self.pos += 1;
+        }
+        bare
+    }
+
+    /// Parse a quoted string, with the escapes [`quote`] writes
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let mut text = String::new();
+
+        loop {
+            let Some(c) = self.peek() else {
+                return Err("unclosed string".to_string());
+            };
+            self.pos += 1;
+
+            match c {
+                '"' => return Ok(text),
+                '\\' => {
+                    let Some(escaped) = self.peek() else {
+                        return Err("unclosed string".to_string());
+                    };
+                    self.pos += 1;
+                    match escaped {
+                        'n' => text.push('\n'),
+                        'r' => text.push('\r'),
+                        't' => text.push('\t'),
+                        'b' => text.push('\x08'),
+                        'f' => text.push('\x0c'),
+                        '"' | '\\' => text.push(escaped),
+                        'u' => {
+                            let hex: String = self.chars[self.pos..]
+                                .iter()
+                                .skip(1)
+                                .take_while(|&&c| c != '}')
+                                .collect();
+                            self.pos += hex.len() + 2;
+                            match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                                Some(c) => text.push(c),
+                                None => return Err(format!("invalid escape `\\u{{{}}}`", hex)),
+                            }
+                        }
+                        c => return Err(format!("invalid escape `\\{}`", c)),
+                    }
+                }
+                c => text.push(c),
+            }
+        }
+    }
+
+    /// An error for a character that doesn't start what's `expected`
+    fn unexpected(&self, expected: &str) -> String {
+        match self.peek() {
+            Some(c) => format!("expected {}, found `{}`", expected, c),
+            None => format!("expected {}", expected),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    fn table(entries: &[(&str, Value)]) -> Value {
+        Value::Table(
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn escapes_round_trip() {
+        let tricky = "quote \" backslash \\ line\nbreak\ttab \x08\x0c \x01 ünïcode";
+        let written = quote(tricky);
+        assert_eq!(
+            written,
+            "\"quote \\\" backslash \\\\ line\\nbreak\\ttab \\b\\f \\u{1} ünïcode\""
+        );
+        assert_eq!(
+            parse(&format!("key {}", written)).unwrap(),
+            table(&[("key", text(tricky))])
+        );
+    }
+
+    #[test]
+    fn string_escapes() {
+        let parsed = parse(r#"key "a\u{1F600}b\"c\\d""#).unwrap();
+        assert_eq!(parsed, table(&[("key", text("a\u{1F600}b\"c\\d"))]));
+    }
+
+    #[test]
+    fn keywords_and_numbers() {
+        let parsed = parse("a #true; b #false; c #null; d -31; e 1.5e3; f #inf; g #-inf").unwrap();
+        assert_eq!(
+            parsed,
+            table(&[
+                ("a", Value::Bool(true)),
+                ("b", Value::Bool(false)),
+                ("c", Value::Null),
+                ("d", Value::Integer(-31)),
+                ("e", Value::Float(1500.0)),
+                ("f", Value::Float(f64::INFINITY)),
+                ("g", Value::Float(f64::NEG_INFINITY)),
+            ])
+        );
+    }
+
+    #[test]
+    fn slashdash_comments_out_nodes() {
+        let parsed = parse(
+            r#"
+            // Port to listen on
+            /-port 8080
+
+            /-server {
+                host "ignored"
+            }
+            name "x"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(parsed, table(&[("name", text("x"))]));
+    }
+
+    #[test]
+    fn nested_children_and_lists() {
+        let parsed = parse(
+            r#"
+            server {
+                tls {
+                    cert "/etc/cert.pem"
+                    ciphers { - "a"; - "b" }
+                }
+                "quoted name" 1
+            }
+            empty {}
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed,
+            table(&[
+                (
+                    "server",
+                    table(&[
+                        (
+                            "tls",
+                            table(&[
+                                ("cert", text("/etc/cert.pem")),
+                                ("ciphers", Value::Array(vec![text("a"), text("b")])),
+                            ])
+                        ),
+                        ("quoted name", Value::Integer(1)),
+                    ])
+                ),
+                ("empty", table(&[])),
+            ])
+        );
+    }
+
+    #[test]
+    fn malformed_input_is_an_error() {
+        let cases = [
+            ("key \"unclosed", "line 1: unclosed string"),
+            ("server {\n    port 1\n", "line 3: expected `}`"),
+            ("port 1\n}", "line 2: unexpected `}`"),
+            ("key \"\\q\"", "line 1: invalid escape `\\q`"),
+            (
+                "key \"\\u{110000}\"",
+                "line 1: invalid escape `\\u{110000}`",
+            ),
+            ("key #maybe", "line 1: unknown keyword `#maybe`"),
+            ("key", "line 1: `key` has no value"),
+            ("(u8)key 1", "line 1: expected a node name, found `(`"),
+            // What the renderer never writes isn't read either
+            ("key a=1", "line 1: expected a value, found `a`"),
+            ("key 1 2", "line 1: `key` has more than one value"),
+            ("key #\"raw\"#", "line 1: unknown keyword `#`"),
+            ("key 0x1F", "line 1: expected a value, found `0`"),
+        ];
+        for (input, message) in cases {
+            assert_eq!(parse(input).unwrap_err(), message, "{:?}", input);
+        }
+
+        assert_eq!(parse("a 1\na 2").unwrap_err(), "`a` appears more than once");
+        assert_eq!(
+            parse("a 1 { b 2 }").unwrap_err(),
+            "line 1: `a` has more than one value"
+        );
+    }
+
+    #[test]
+    fn values_round_trip() {
+        let value = table(&[
+            ("name", text("a \"b\"")),
+            (
+                "ports",
+                Value::Array(vec![Value::Integer(1), Value::Integer(2)]),
+            ),
+            ("true", Value::Bool(true)),
+            ("limits", table(&[("max-size", Value::Float(1.5))])),
+            ("empty", Value::Array(Vec::new())),
+        ]);
+        let expected = table(&[
+            ("name", text("a \"b\"")),
+            (
+                "ports",
+                Value::Array(vec![Value::Integer(1), Value::Integer(2)]),
+            ),
+            ("true", Value::Bool(true)),
+            ("limits", table(&[("max-size", Value::Float(1.5))])),
+            // An empty list and an empty table are both written as `{}`
+            ("empty", table(&[])),
+        ]);
+        assert_eq!(parse(&format_document(&value)).unwrap(), expected);
+    }
+}
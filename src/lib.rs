@@ -4,41 +4,124 @@
 //! This crate generates documentation for configuration options defined in Rust
 //! structs that use serde for serialization/deserialization.
 
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 pub use serde_config_docs_derive::ConfigDocs;
-
-use std::fmt::{self, Write};
-
-/// Options to customize the structure of the output Markdown document
-#[derive(Debug, Clone)]
-pub struct MarkdownOptions {
-    pub title: Option<String>,
-    pub format: ConfigFormat,
-}
+// Used by the derive macro to register types
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+pub use inventory;
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+mod check;
+#[cfg(feature = "dotenv")]
+mod dotenv;
+mod embed;
+mod example;
+mod export;
+#[cfg(any(feature = "ini", feature = "properties"))]
+mod flat;
+#[cfg(any(feature = "ini", feature = "properties", feature = "dotenv"))]
+mod flat_value;
+#[cfg(feature = "hcl")]
+mod hcl;
+mod inject;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "jsonc")]
+mod jsonc;
+#[cfg(feature = "kdl")]
+mod kdl;
+mod markdown;
+mod render;
+#[cfg(feature = "registry")]
+mod registry;
+#[cfg(feature = "ron")]
+mod ron;
+#[cfg(feature = "toml")]
+mod toml;
+mod value;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+pub use check::{update_docs_file, DocsError, DocsMode};
+pub use embed::write_build_docs;
+pub use example::{
+    generate_example, generate_example_config, generate_example_config_with_options,
+    try_generate_example, try_generate_example_config, try_generate_example_config_with_options,
+    verify_example_config, ExampleOptions, ShapeError, VerifyError,
+};
+pub use export::{export_docs, ExportError, ExportSettings};
+pub use inject::{inject_config_docs, inject_docs, inject_docs_into_file, InjectError};
+pub use markdown::{
+    generate_combined_docs, generate_config_doc_pages, generate_config_docs_with_options,
+    generate_markdown, generate_markdown_pages, try_generate_combined_docs,
+    try_generate_config_doc_pages, try_generate_config_docs_with_options, try_generate_markdown,
+    try_generate_markdown_pages, MarkdownLayout, MarkdownOptions, TableOfContents,
+};
+pub use render::{
+    visit_deserializer, write_annotation, write_comment_lines, Annotation, ExampleKey,
+    RenderContext, Renderer, Visit,
+};
+#[cfg(feature = "registry")]
+pub use registry::{
+    export_registered_docs, generate_registered_docs, registered_configs,
+    try_generate_registered_docs, RegisteredConfig,
+};
+pub use value::{from_value, to_value, Value, ValueError, VariantData};
 
 /// The serialization format to display examples in
 #[derive(Debug, Clone, Copy)]
 pub enum ConfigFormat {
-    // #[cfg(toml)]
+    #[cfg(feature = "toml")]
     Toml,
-    // Other formats could be added later:
-    // Json,
-    // Yaml,
+    /// JSON has no comments, so docs are written around the example instead
+    #[cfg(feature = "json")]
+    Json,
+    /// Nested sections are written as indented mappings in one document
+    #[cfg(feature = "yaml")]
+    Yaml,
+    /// JSON with `//` comments and trailing commas, as used by VS Code
+    #[cfg(feature = "jsonc")]
+    Jsonc,
+    /// Rusty Object Notation, with sections written as named structs
+    #[cfg(feature = "ron")]
+    Ron,
+    /// INI with `[server.tls]` section headers and `;` comments
+    #[cfg(feature = "ini")]
+    Ini,
+    /// Java properties with full dotted keys, e.g. `server.tls.cert=...`
+    #[cfg(feature = "properties")]
+    Properties,
+    /// A `.env` file with one variable per key, e.g. `APP_SERVER__PORT=8080`
+    #[cfg(feature = "dotenv")]
+    Dotenv,
+    /// KDL, with sections as nodes and keys as child nodes, e.g. `port 8080`
+    ///
+    /// [`ConfigFormat::parse`] only reads the subset of KDL this crate
+    /// writes, which is enough to check generated examples.
+    #[cfg(feature = "kdl")]
+    Kdl,
+    /// HCL, with sections as blocks and lists or maps of structs as
+    /// repeated or labeled blocks
+    #[cfg(feature = "hcl")]
+    Hcl,
+    /// A format implemented outside this crate
+    Custom(&'static dyn Renderer),
 }
 
-impl MarkdownOptions {
-    /// Set the configuration format to display examples in
-    pub fn new(format: ConfigFormat) -> Self {
-        MarkdownOptions {
-            title: None,
-            format,
-        }
-    }
-    /// Set a custom title to use in the generated document
-    pub fn title(mut self, title: Option<String>) -> Self {
-        self.title = title;
-        self
-    }
+/// How keys that have a default value are written in examples
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DefaultsStyle {
+    /// Write every key with its default value, e.g. `port = 8080`
+    #[default]
+    Explicit,
+    /// Comment out keys that have a default, e.g. `# port = 8080`, the way
+    /// nginx or PostgreSQL ship their config files. Required keys and keys
+    /// with an example value stay active.
+    CommentedOut,
 }
 
 /// Information about a configuration field
@@ -47,8 +130,12 @@ pub struct FieldInfo {
     /// The name of the field as it appears in the serialized format
     pub name: String,
     pub doc_comments: Option<String>,
-    pub default_value: Option<String>,
+    pub default_value: Option<Value>,
+    /// A value to show in examples instead of the default or a placeholder
+    pub example_value: Option<Value>,
     pub field_type: String,
+    /// Whether the field must be present because serde has no default for it
+    pub required: bool,
     pub is_nested: bool,
     pub nested_fields: Vec<FieldInfo>,
 }
@@ -60,7 +147,9 @@ impl FieldInfo {
             name: name.into(),
             doc_comments: None,
             default_value: None,
+            example_value: None,
             field_type: "".to_string(),
+            required: false,
             is_nested: false,
             nested_fields: Vec::new(),
         }
@@ -73,17 +162,44 @@ impl FieldInfo {
     }
 
     /// Set the default value for this field
-    pub fn default(mut self, default: impl Into<Option<String>>) -> Self {
+    pub fn default(mut self, default: impl Into<Option<Value>>) -> Self {
         self.default_value = default.into();
         self
     }
 
+    /// Set the default value for this field by serializing it
+    ///
+    /// Values that can't be represented as a [`Value`] leave the field
+    /// without a default.
+    pub fn default_from<T: Serialize + ?Sized>(mut self, default: &T) -> Self {
+        self.default_value = to_value(default).ok();
+        self
+    }
+
     /// Set the type of this field
     pub fn field_type(mut self, field_type: impl Into<String>) -> Self {
         self.field_type = field_type.into();
         self
     }
 
+    /// Set an example value to show for this field
+    pub fn example(mut self, example: impl Into<Option<Value>>) -> Self {
+        self.example_value = example.into();
+        self
+    }
+
+    /// Set an example value to show for this field by serializing it
+    pub fn example_from<T: Serialize + ?Sized>(mut self, example: &T) -> Self {
+        self.example_value = to_value(example).ok();
+        self
+    }
+
+    /// Mark whether this field has to be set in every config file
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
     /// Make this field a nested section with child fields
     pub fn nested(mut self, nested_fields: Vec<FieldInfo>) -> Self {
         self.is_nested = true;
@@ -95,13 +211,32 @@ impl FieldInfo {
 /// Builder for a config schema
 #[derive(Debug, Default)]
 pub struct ConfigSchemaBuilder {
+    name: Option<String>,
+    env_prefix: Option<String>,
     fields: Vec<FieldInfo>,
 }
 
 impl ConfigSchemaBuilder {
     /// Create a new config schema builder
     pub fn new() -> Self {
-        Self { fields: Vec::new() }
+        Self {
+            name: None,
+            env_prefix: None,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Set the name of the type this schema describes
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the prefix of environment variable names, e.g. `APP` for
+    /// `APP_SERVER__PORT`
+    pub fn env_prefix(mut self, env_prefix: impl Into<String>) -> Self {
+        self.env_prefix = Some(env_prefix.into());
+        self
     }
 
     /// Add a field to the schema
@@ -113,6 +248,8 @@ impl ConfigSchemaBuilder {
     /// Build the schema
     pub fn build(self) -> ConfigSchema {
         ConfigSchema {
+            name: self.name,
+            env_prefix: self.env_prefix,
             fields: self.fields,
         }
     }
@@ -121,6 +258,10 @@ impl ConfigSchemaBuilder {
 /// A schema describing a configuration structure
 #[derive(Debug)]
 pub struct ConfigSchema {
+    /// The name of the type this schema describes, if known
+    pub name: Option<String>,
+    /// The prefix of environment variable names, if any
+    pub env_prefix: Option<String>,
     pub fields: Vec<FieldInfo>,
 }
 
@@ -131,106 +272,134 @@ impl ConfigSchema {
     }
 
     /// Generate markdown documentation for this schema with custom options
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schema holds a value the format can't express, e.g. an
+    /// array of tables in INI. Use
+    /// [`try_generate_docs_with_options`](Self::try_generate_docs_with_options)
+    /// to get a [`ShapeError`] instead.
     pub fn generate_docs_with_options(&self, options: &MarkdownOptions) -> String {
-        generate_markdown(&self.fields, options)
+        self.try_generate_docs_with_options(options)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
-}
 
-/// Generate markdown documentation for a list of fields
-pub fn generate_markdown(fields: &[FieldInfo], options: &MarkdownOptions) -> String {
-    let mut buffer = String::new();
+    /// Generate markdown documentation for this schema with custom options
+    ///
+    /// Fails if the schema holds a value the format can't express, e.g. an
+    /// array of tables in INI.
+    pub fn try_generate_docs_with_options(
+        &self,
+        options: &MarkdownOptions,
+    ) -> Result<String, ShapeError> {
+        markdown::schema_markdown(
+            self.name.as_deref(),
+            self.env_prefix.as_deref(),
+            &self.fields,
+            options,
+        )
+    }
 
-    if let Some(title) = &options.title {
-        writeln!(buffer, "# {}", title).unwrap();
-        writeln!(buffer).unwrap();
+    /// Generate one markdown page per top-level section of this schema, plus
+    /// an `index.md` page linking to them, keyed by file name
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schema holds a value the format can't express. Use
+    /// [`try_generate_doc_pages`](Self::try_generate_doc_pages) to get a
+    /// [`ShapeError`] instead.
+    pub fn generate_doc_pages(&self, options: &MarkdownOptions) -> BTreeMap<String, String> {
+        self.try_generate_doc_pages(options)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
 
-    for field in fields {
-        write_field_docs(&mut buffer, field, &options.format, 0, "").unwrap();
+    /// Generate one markdown page per top-level section of this schema, or
+    /// fail if the format can't express it
+    pub fn try_generate_doc_pages(
+        &self,
+        options: &MarkdownOptions,
+    ) -> Result<BTreeMap<String, String>, ShapeError> {
+        markdown::schema_pages(
+            self.name.as_deref(),
+            self.env_prefix.as_deref(),
+            &self.fields,
+            options,
+        )
     }
 
-    buffer
-}
+    /// Generate a commented example config file for this schema with custom options
+    ///
+    /// # Panics
+    ///
+    /// Panics if the schema holds a value the format can't express. Use
+    /// [`try_generate_example_with_options`](Self::try_generate_example_with_options)
+    /// to get a [`ShapeError`] instead.
+    pub fn generate_example_with_options(&self, options: &ExampleOptions) -> String {
+        self.try_generate_example_with_options(options)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
 
-/// Write documentation for a field and its nested fields
-fn write_field_docs(
-    buffer: &mut String,
-    field: &FieldInfo,
-    format: &ConfigFormat,
-    depth: usize,
-    path: &str,
-) -> fmt::Result {
-    if field.is_nested {
-        // Capitalize section name for header
-        let section_name = capitalize(&field.name);
-
-        writeln!(buffer, "## {}", section_name)?;
-
-        if let Some(doc) = &field.doc_comments {
-            writeln!(buffer)?;
-            writeln!(buffer, "{}", doc)?;
-        }
+    /// Generate a commented example config file for this schema with custom
+    /// options, or fail if the format can't express it
+    pub fn try_generate_example_with_options(
+        &self,
+        options: &ExampleOptions,
+    ) -> Result<String, ShapeError> {
+        example::generate_named_example(
+            self.name.as_deref(),
+            self.env_prefix.as_deref(),
+            &self.fields,
+            options,
+        )
+    }
 
-        match format {
-            // #[cfg(toml)]
-            ConfigFormat::Toml => {
-                writeln!(buffer, "```toml")?;
-                writeln!(buffer, "[{}]", field.name)?;
-                writeln!(buffer)?;
-
-                for nested_field in &field.nested_fields {
-                    if !nested_field.is_nested {
-                        if let Some(doc) = &nested_field.doc_comments {
-                            for line in doc.lines() {
-                                writeln!(buffer, "# {}", line)?;
-                            }
-                        }
-
-                        if let Some(default) = &nested_field.default_value {
-                            writeln!(buffer, "# Default: {}", default)?;
-                        }
-
-                        let value_str = match &nested_field.default_value {
-                            Some(val) => format.format_value(&val),
-                            None => "...".to_string(),
-                        };
-
-                        writeln!(buffer, "{} = {}", nested_field.name, value_str)?;
-                        writeln!(buffer)?;
-                    }
-                }
+    /// The environment variable for the key at `path`, e.g.
+    /// `APP_SERVER__PORT` for `["server", "port"]` with the prefix `APP`
+    pub fn env_var(&self, path: &[&str]) -> String {
+        env_var_name(self.env_prefix.as_deref(), path)
+    }
 
-                writeln!(buffer, "```")?;
-            }
-            _ => unimplemented!("no config format specified!!"),
-        }
+    /// Deserialize a config file written in the given format for this schema
+    ///
+    /// Unlike [`ConfigFormat::parse`], this can read formats that need the
+    /// schema to map keys back, like env files.
+    pub fn parse<T: DeserializeOwned>(&self, format: ConfigFormat, input: &str) -> Result<T, String> {
+        format.parse_with_schema(input, Some(self))
+    }
+}
 
-        writeln!(buffer)?;
+/// Build an environment variable name from a key path
+///
+/// Each key is upper-cased with camelCase split into words, and sections are
+/// separated by a double underscore, e.g. `SERVER__MAX_CONNECTIONS`.
+pub(crate) fn env_var_name(env_prefix: Option<&str>, path: &[&str]) -> String {
+    let mut name = String::new();
 
-        // Recursively document nested fields
-        let current_path = if path.is_empty() {
-            field.name.clone()
-        } else {
-            format!("{}.{}", path, field.name)
-        };
+    if let Some(prefix) = env_prefix.filter(|p| !p.is_empty()) {
+        name.push_str(&prefix.to_uppercase());
+        name.push('_');
+    }
+
+    for (i, key) in path.iter().enumerate() {
+        if i > 0 {
+            name.push_str("__");
+        }
 
-        for nested_field in &field.nested_fields {
-            if nested_field.is_nested {
-                write_field_docs(buffer, nested_field, format, depth + 1, &current_path)?;
+        let mut previous: Option<char> = None;
+        for c in key.chars() {
+            if c.is_uppercase() && previous.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit()) {
+                name.push('_');
+            }
+            if c.is_alphanumeric() {
+                name.extend(c.to_uppercase());
+            } else {
+                name.push('_');
             }
+            previous = Some(c);
         }
     }
 
-    Ok(())
-}
-
-/// Capitalize the first letter of a string
-fn capitalize(s: &str) -> String {
-    let mut c = s.chars();
-    match c.next() {
-        None => String::new(),
-        Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
-    }
+    name
 }
 
 /// Trait for structs that can generate config documentation
@@ -239,33 +408,380 @@ pub trait ConfigDocsStruct {
     fn schema() -> ConfigSchema;
 }
 
-/// Generate markdown documentation with custom options for a type that implements ConfigDocsStruct
-pub fn generate_config_docs_with_options<T: ConfigDocsStruct>(options: &MarkdownOptions) -> String {
-    T::schema().generate_docs_with_options(options)
-}
-
 impl ConfigFormat {
-    /// Get the file extension for this format
-    pub fn extension(&self) -> &'static str {
+    /// The renderer that writes and reads this format
+    pub fn renderer(&self) -> &'static dyn Renderer {
         match self {
-            // #[cfg(toml)]
-            ConfigFormat::Toml => "toml",
-            _ => unimplemented!("no config format specified!!"),
+            #[cfg(feature = "toml")]
+            ConfigFormat::Toml => &toml::Toml,
+            #[cfg(feature = "json")]
+            ConfigFormat::Json => &json::Json,
+            #[cfg(feature = "yaml")]
+            ConfigFormat::Yaml => &yaml::Yaml,
+            #[cfg(feature = "jsonc")]
+            ConfigFormat::Jsonc => &jsonc::Jsonc,
+            #[cfg(feature = "ron")]
+            ConfigFormat::Ron => &ron::Ron,
+            #[cfg(feature = "ini")]
+            ConfigFormat::Ini => &flat::Flavor::Ini,
+            #[cfg(feature = "properties")]
+            ConfigFormat::Properties => &flat::Flavor::Properties,
+            #[cfg(feature = "dotenv")]
+            ConfigFormat::Dotenv => &dotenv::Dotenv,
+            #[cfg(feature = "kdl")]
+            ConfigFormat::Kdl => &kdl::Kdl,
+            #[cfg(feature = "hcl")]
+            ConfigFormat::Hcl => &hcl::Hcl,
+            ConfigFormat::Custom(renderer) => *renderer,
+        }
+    }
+
+    /// Every format built into this crate with the enabled features
+    pub fn builtin() -> &'static [ConfigFormat] {
+        &[
+            #[cfg(feature = "toml")]
+            ConfigFormat::Toml,
+            #[cfg(feature = "json")]
+            ConfigFormat::Json,
+            #[cfg(feature = "yaml")]
+            ConfigFormat::Yaml,
+            #[cfg(feature = "jsonc")]
+            ConfigFormat::Jsonc,
+            #[cfg(feature = "ron")]
+            ConfigFormat::Ron,
+            #[cfg(feature = "ini")]
+            ConfigFormat::Ini,
+            #[cfg(feature = "properties")]
+            ConfigFormat::Properties,
+            #[cfg(feature = "dotenv")]
+            ConfigFormat::Dotenv,
+            #[cfg(feature = "kdl")]
+            ConfigFormat::Kdl,
+            #[cfg(feature = "hcl")]
+            ConfigFormat::Hcl,
+        ]
+    }
+
+    /// Make a format implemented outside this crate available by name, e.g.
+    /// to `CONFIG_DOCS_FORMAT`
+    ///
+    /// Registered formats are looked up before built-in ones, so they can
+    /// also take over a built-in format's name.
+    pub fn register(renderer: &'static dyn Renderer) {
+        render::register(renderer);
+    }
+
+    /// Look up a format by its name, as used in `CONFIG_DOCS_FORMAT`
+    pub fn from_name(name: &str) -> Option<Self> {
+        render::find_registered(name).or_else(|| {
+            Self::builtin()
+                .iter()
+                .find(|format| render::matches_name(format.renderer(), name))
+                .copied()
+        })
+    }
+
+    /// The Cargo feature that compiles in the built-in format called `name`,
+    /// whether or not it's enabled
+    pub fn feature_for(name: &str) -> Option<&'static str> {
+        BUILTIN_FEATURES
+            .iter()
+            .find(|(builtin, _)| builtin.eq_ignore_ascii_case(name))
+            .map(|(_, feature)| *feature)
+    }
+
+    /// The format named by the `CONFIG_DOCS_FORMAT` environment variable, or
+    /// the first built-in format if it's unset, which is TOML by default
+    pub fn from_env() -> Result<Self, FormatNameError> {
+        match std::env::var("CONFIG_DOCS_FORMAT") {
+            Ok(name) if !name.is_empty() => name.parse(),
+            _ => Self::builtin()
+                .first()
+                .copied()
+                .ok_or(FormatNameError::NoneEnabled),
         }
     }
 
+    /// Get the file extension for this format
+    pub fn extension(&self) -> &'static str {
+        self.renderer().extension()
+    }
+
+    /// Check that every key and value in a schema can be written in this format
+    ///
+    /// Flat formats like INI can't express arrays of tables, for example.
+    pub fn validate(&self, fields: &[FieldInfo]) -> Result<(), ShapeError> {
+        self.renderer().validate(fields)
+    }
+
     /// Format a value appropriately for this format
-    pub fn format<T: Serialize + std::fmt::Debug>(&self, value: T) -> String {
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value can't be serialized or written in this format.
+    /// Use [`ConfigFormat::try_format`] to get a [`ShapeError`] instead.
+    pub fn format<T: Serialize>(&self, value: T) -> String {
+        self.try_format(value)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Format a value appropriately for this format, or fail if it can't be
+    /// serialized or written in this format
+    pub fn try_format<T: Serialize>(&self, value: T) -> Result<String, ShapeError> {
+        let value = self.serialize(&value)?;
+        self.renderer().render_document(&value)
+    }
+
+    /// Format a value on a single line, as written after a key
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value can't be serialized. Use
+    /// [`ConfigFormat::try_format_value`] to get a [`ShapeError`] instead.
+    pub fn format_value<T: Serialize>(&self, value: T) -> String {
+        self.try_format_value(value)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Format a value on a single line, or fail if it can't be serialized
+    pub fn try_format_value<T: Serialize>(&self, value: T) -> Result<String, ShapeError> {
+        let value = self.serialize(&value)?;
+        Ok(self.render_value(&value))
+    }
+
+    /// Serialize a value to be formatted, reporting errors as a [`ShapeError`]
+    fn serialize<T: Serialize>(&self, value: &T) -> Result<Value, ShapeError> {
+        to_value(value).map_err(|err| ShapeError {
+            format: *self,
+            path: String::new(),
+            reason: err.to_string(),
+        })
+    }
+
+    /// Format a value on a single line, as written after a key
+    ///
+    /// Unlike [`ConfigFormat::format_value`], this keeps enum variants intact
+    /// for formats with their own enum syntax.
+    pub fn render_value(&self, value: &Value) -> String {
+        self.renderer().render_value(value)
+    }
+
+    /// Deserialize a config file written in this format
+    pub fn parse<T: DeserializeOwned>(&self, input: &str) -> Result<T, String> {
+        self.parse_with_schema(input, None)
+    }
+
+    /// Deserialize a config file, using the schema it was written for if known
+    ///
+    /// Properties files can't write a section whose keys are all commented
+    /// out, so the schema's sections are created up front instead of going
+    /// missing, and env files need it to map variables back to keys.
+    pub(crate) fn parse_with_schema<T: DeserializeOwned>(
+        &self,
+        input: &str,
+        schema: Option<&ConfigSchema>,
+    ) -> Result<T, String> {
+        let mut parsed = None;
+        self.renderer()
+            .deserialize(input, schema, &mut |deserializer| {
+                parsed = Some(erased_serde::deserialize(deserializer)?);
+                Ok(())
+            })?;
+
+        parsed.ok_or_else(|| format!("the {} renderer didn't read anything", self.extension()))
+    }
+}
+
+impl std::str::FromStr for ConfigFormat {
+    type Err = FormatNameError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        if let Some(format) = Self::from_name(name) {
+            return Ok(format);
+        }
+
+        Err(match Self::feature_for(name) {
+            Some(feature) => FormatNameError::Disabled {
+                name: name.to_string(),
+                feature,
+            },
+            None => FormatNameError::Unknown(name.to_string()),
+        })
+    }
+}
+
+/// The names of every built-in format and the Cargo feature it needs, whether
+/// or not it's compiled in
+const BUILTIN_FEATURES: &[(&str, &str)] = &[
+    ("toml", "toml"),
+    ("json", "json"),
+    ("yaml", "yaml"),
+    ("yml", "yaml"),
+    ("jsonc", "jsonc"),
+    ("json5", "jsonc"),
+    ("ron", "ron"),
+    ("ini", "ini"),
+    ("properties", "properties"),
+    ("env", "dotenv"),
+    ("dotenv", "dotenv"),
+    ("kdl", "kdl"),
+    ("hcl", "hcl"),
+    ("tf", "hcl"),
+];
+
+/// Error returned when a format name doesn't match any available format
+#[derive(Debug, Clone)]
+pub enum FormatNameError {
+    /// A built-in format whose Cargo feature isn't enabled
+    Disabled { name: String, feature: &'static str },
+    /// A name that doesn't match any format
+    Unknown(String),
+    /// No format was named and there's no built-in one to fall back to
+    NoneEnabled,
+}
+
+impl fmt::Display for FormatNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            // #[cfg(toml)]
-            ConfigFormat::Toml => toml::to_string(dbg!(&value)).unwrap(),
-            _ => unimplemented!("no config format specified!!"),
+            FormatNameError::Disabled { name, feature } => write!(
+                f,
+                "the `{}` format isn't compiled in, enable the `{}` feature of serde-config-docs",
+                name, feature
+            ),
+            FormatNameError::Unknown(name) => {
+                let mut available: Vec<String> = ConfigFormat::builtin()
+                    .iter()
+                    .map(|format| format.renderer().name().to_string())
+                    .collect();
+                available.extend(render::registered_names());
+
+                write!(f, "unknown format `{}`", name)?;
+                if available.is_empty() {
+                    Ok(())
+                } else {
+                    write!(f, ", expected one of `{}`", available.join("`, `"))
+                }
+            }
+            FormatNameError::NoneEnabled => write!(
+                f,
+                "no output format is compiled in, enable a feature of serde-config-docs \
+                 such as `toml`"
+            ),
         }
     }
+}
 
-    pub fn format_value<T: Serialize>(&self, value: T) -> String {
-        let mut res = String::new();
-        serde::Serialize::serialize(&value, toml::ser::ValueSerializer::new(&mut res)).unwrap();
-        res
+impl std::error::Error for FormatNameError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_builtin_format_is_found_by_name() {
+        for format in ConfigFormat::builtin() {
+            let renderer = format.renderer();
+            for name in std::iter::once(renderer.name()).chain(renderer.aliases().iter().copied()) {
+                let found: ConfigFormat = name.to_uppercase().parse().unwrap();
+                assert_eq!(found.extension(), format.extension(), "{}", name);
+                assert!(ConfigFormat::feature_for(name).is_some(), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn feature_for_knows_disabled_formats() {
+        assert_eq!(ConfigFormat::feature_for("yml"), Some("yaml"));
+        assert_eq!(ConfigFormat::feature_for("TF"), Some("hcl"));
+        assert_eq!(ConfigFormat::feature_for("env"), Some("dotenv"));
+        assert_eq!(ConfigFormat::feature_for("xml"), None);
+    }
+
+    #[test]
+    fn unknown_formats_list_the_available_ones() {
+        let err = "xml".parse::<ConfigFormat>().unwrap_err();
+        assert!(matches!(&err, FormatNameError::Unknown(name) if name == "xml"));
+
+        let message = err.to_string();
+        assert!(message.starts_with("unknown format `xml`"), "{}", message);
+        for format in ConfigFormat::builtin() {
+            let name = format!("`{}`", format.renderer().name());
+            assert!(message.contains(&name), "{}", message);
+        }
+    }
+
+    #[cfg(not(feature = "kdl"))]
+    #[test]
+    fn disabled_formats_name_their_feature() {
+        let err = "KDL".parse::<ConfigFormat>().unwrap_err();
+        assert!(matches!(
+            &err,
+            FormatNameError::Disabled { feature: "kdl", .. }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "the `KDL` format isn't compiled in, enable the `kdl` feature of serde-config-docs"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_is_the_first_builtin_format() {
+        assert!(matches!(ConfigFormat::builtin()[0], ConfigFormat::Toml));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn try_format_reports_values_the_format_rejects() {
+        let err = ConfigFormat::Toml.try_format(8080).unwrap_err();
+        assert_eq!(err.path, "");
+        let message = err.to_string();
+        assert!(
+            message.starts_with("the value can't be written as toml: "),
+            "{}",
+            message
+        );
+        assert_eq!(ConfigFormat::Toml.try_format_value(8080).unwrap(), "8080");
+    }
+
+    #[cfg(feature = "ini")]
+    #[test]
+    fn try_format_names_the_key_flat_formats_reject() {
+        #[derive(Serialize)]
+        struct Server {
+            host: &'static str,
+        }
+
+        #[derive(Serialize)]
+        struct Cluster {
+            servers: Vec<Server>,
+        }
+
+        let cluster = Cluster {
+            servers: vec![Server { host: "a" }],
+        };
+        let err = ConfigFormat::Ini.try_format(cluster).unwrap_err();
+        assert_eq!(err.path, "servers");
+    }
+
+    #[cfg(not(any(
+        feature = "toml",
+        feature = "json",
+        feature = "yaml",
+        feature = "jsonc",
+        feature = "ron",
+        feature = "ini",
+        feature = "properties",
+        feature = "dotenv",
+        feature = "kdl",
+        feature = "hcl"
+    )))]
+    #[test]
+    fn no_builtin_format_without_features() {
+        assert!(ConfigFormat::builtin().is_empty());
+        assert_eq!(
+            "toml".parse::<ConfigFormat>().unwrap_err().to_string(),
+            "the `toml` format isn't compiled in, enable the `toml` feature of serde-config-docs"
+        );
     }
 }
@@ -0,0 +1,1216 @@
+// serde_config_docs/src/markdown.rs
+//! Generate Markdown documentation, with one heading per section
+//!
+//! Each section is shown as an example snippet in the chosen format, as a
+//! reference table of its keys, or both. An optional table of contents links
+//! to each section and key through anchors named after their dotted path.
+//!
+//! Docs can also be split into one page per top-level section, with an index
+//! page linking to them.
+
+use crate::render::RenderContext;
+use crate::{ConfigDocsStruct, ConfigFormat, ConfigSchema, DefaultsStyle, FieldInfo, ShapeError};
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{self, Write};
+
+/// Options to customize the structure of the output Markdown document
+#[derive(Debug, Clone)]
+pub struct MarkdownOptions {
+    pub title: Option<String>,
+    /// The heading of the section documenting the root's own keys
+    pub root_title: String,
+    pub format: ConfigFormat,
+    pub defaults: DefaultsStyle,
+    pub layout: MarkdownLayout,
+    pub toc: TableOfContents,
+}
+
+/// How each section is shown in the Markdown document
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MarkdownLayout {
+    /// An example snippet in the chosen format. Formats without comments,
+    /// like JSON, get the reference table as well, since their examples
+    /// can't hold any docs.
+    #[default]
+    Examples,
+    /// A table listing each key with its type, default, environment variable
+    /// and description
+    Table,
+    /// The example snippet followed by the reference table
+    Both,
+}
+
+/// What the table of contents at the top of the document links to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TableOfContents {
+    /// No table of contents
+    #[default]
+    None,
+    /// A nested list of sections
+    Sections,
+    /// Each section followed by its keys. Keys link to their row in the
+    /// reference table, or to their section if it only has an example.
+    Keys,
+}
+
+impl MarkdownOptions {
+    /// Set the configuration format to display examples in
+    pub fn new(format: ConfigFormat) -> Self {
+        MarkdownOptions {
+            title: None,
+            root_title: "General".to_string(),
+            format,
+            defaults: DefaultsStyle::default(),
+            layout: MarkdownLayout::default(),
+            toc: TableOfContents::default(),
+        }
+    }
+    /// Set a custom title to use in the generated document
+    pub fn title(mut self, title: Option<String>) -> Self {
+        self.title = title;
+        self
+    }
+    /// Set the heading of the section documenting keys at the root of the
+    /// config, `General` by default
+    pub fn root_title(mut self, root_title: impl Into<String>) -> Self {
+        self.root_title = root_title.into();
+        self
+    }
+    /// Set how keys with default values are written in examples
+    pub fn defaults(mut self, defaults: DefaultsStyle) -> Self {
+        self.defaults = defaults;
+        self
+    }
+    /// Set whether sections are shown as examples, reference tables or both
+    pub fn layout(mut self, layout: MarkdownLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+    /// Set whether the document starts with a table of contents
+    pub fn toc(mut self, toc: TableOfContents) -> Self {
+        self.toc = toc;
+        self
+    }
+}
+
+/// Generate markdown documentation with custom options for a type that implements ConfigDocsStruct
+///
+/// # Panics
+///
+/// Panics if the type holds a value the format can't express, e.g. an array
+/// of tables in INI. Use [`try_generate_config_docs_with_options`] to get a
+/// [`ShapeError`] instead.
+pub fn generate_config_docs_with_options<T: ConfigDocsStruct>(options: &MarkdownOptions) -> String {
+    T::schema().generate_docs_with_options(options)
+}
+
+/// Generate markdown documentation with custom options for a type that
+/// implements ConfigDocsStruct
+///
+/// Fails if the type holds a value the format can't express, e.g. an array
+/// of tables in INI.
+pub fn try_generate_config_docs_with_options<T: ConfigDocsStruct>(
+    options: &MarkdownOptions,
+) -> Result<String, ShapeError> {
+    T::schema().try_generate_docs_with_options(options)
+}
+
+/// Generate markdown documentation for a list of fields
+///
+/// # Panics
+///
+/// Panics if the fields hold a value the format can't express. Use
+/// [`try_generate_markdown`] to get a [`ShapeError`] instead.
+pub fn generate_markdown(fields: &[FieldInfo], options: &MarkdownOptions) -> String {
+    try_generate_markdown(fields, options).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Generate markdown documentation for a list of fields
+///
+/// Fails if the fields hold a value the format can't express, e.g. an array
+/// of tables in INI.
+pub fn try_generate_markdown(
+    fields: &[FieldInfo],
+    options: &MarkdownOptions,
+) -> Result<String, ShapeError> {
+    schema_markdown(None, None, fields, options)
+}
+
+/// Generate one markdown page per top-level section for a type that
+/// implements ConfigDocsStruct, plus an `index.md` page linking to them
+///
+/// # Panics
+///
+/// Panics if the type holds a value the format can't express. Use
+/// [`try_generate_config_doc_pages`] to get a [`ShapeError`] instead.
+pub fn generate_config_doc_pages<T: ConfigDocsStruct>(
+    options: &MarkdownOptions,
+) -> BTreeMap<String, String> {
+    T::schema().generate_doc_pages(options)
+}
+
+/// Generate one markdown page per top-level section for a type that
+/// implements ConfigDocsStruct, or fail if the format can't express it
+pub fn try_generate_config_doc_pages<T: ConfigDocsStruct>(
+    options: &MarkdownOptions,
+) -> Result<BTreeMap<String, String>, ShapeError> {
+    T::schema().try_generate_doc_pages(options)
+}
+
+/// Generate markdown documentation for a list of fields as a set of pages,
+/// keyed by file name
+///
+/// Each top-level section gets a page of its own, named after the section,
+/// e.g. `server.md`. The `index.md` page links to each of them with the
+/// first paragraph of its docs, and documents the keys at the root.
+///
+/// # Panics
+///
+/// Panics if the fields hold a value the format can't express. Use
+/// [`try_generate_markdown_pages`] to get a [`ShapeError`] instead.
+pub fn generate_markdown_pages(
+    fields: &[FieldInfo],
+    options: &MarkdownOptions,
+) -> BTreeMap<String, String> {
+    try_generate_markdown_pages(fields, options).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Generate markdown documentation for a list of fields as a set of pages,
+/// or fail if the format can't express them
+pub fn try_generate_markdown_pages(
+    fields: &[FieldInfo],
+    options: &MarkdownOptions,
+) -> Result<BTreeMap<String, String>, ShapeError> {
+    schema_pages(None, None, fields, options)
+}
+
+/// Generate markdown documentation for the fields of a schema, naming
+/// environment variables with the given prefix
+pub(crate) fn schema_markdown(
+    name: Option<&str>,
+    env_prefix: Option<&str>,
+    fields: &[FieldInfo],
+    options: &MarkdownOptions,
+) -> Result<String, ShapeError> {
+    let ctx = context(name, env_prefix, fields, options)?;
+
+    let mut document = Document::new(options, ctx, 2);
+    document.write_root(fields).unwrap();
+    for field in fields {
+        document.write_section(field, 0, &[]).unwrap();
+    }
+
+    Ok(finish_document(options, &document.toc, &document.body))
+}
+
+/// Generate markdown documentation for several root types in one document
+///
+/// Each schema gets a `##` heading with its name, and its sections are one
+/// level below it. Anchors start with the schema's name, so sections that
+/// share a name in different schemas still get their own.
+///
+/// # Panics
+///
+/// Panics if a schema holds a value the format can't express. Use
+/// [`try_generate_combined_docs`] to get a [`ShapeError`] instead.
+pub fn generate_combined_docs(schemas: &[ConfigSchema], options: &MarkdownOptions) -> String {
+    try_generate_combined_docs(schemas, options).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Generate markdown documentation for several root types in one document,
+/// or fail if the format can't express one of them
+pub fn try_generate_combined_docs(
+    schemas: &[ConfigSchema],
+    options: &MarkdownOptions,
+) -> Result<String, ShapeError> {
+    let schemas: Vec<(&str, &ConfigSchema)> = schemas
+        .iter()
+        .map(|schema| (schema.name.as_deref().unwrap_or("Config"), schema))
+        .collect();
+    combined_markdown(&schemas, options)
+}
+
+/// Generate markdown documentation for several schemas in one document, each
+/// under a heading with the given name
+///
+/// A name can be a path like `my_crate::Config`, whose anchors start with
+/// `my_crate-config`.
+pub(crate) fn combined_markdown(
+    schemas: &[(&str, &ConfigSchema)],
+    options: &MarkdownOptions,
+) -> Result<String, ShapeError> {
+    let mut toc = Vec::new();
+    let mut body = String::new();
+    let mut used_anchors = HashSet::new();
+
+    for &(name, schema) in schemas {
+        let anchor: Vec<&str> = name.split("::").collect();
+        let anchor = unique_anchor(&mut used_anchors, slug(&anchor));
+        let ctx = context(
+            schema.name.as_deref(),
+            schema.env_prefix.as_deref(),
+            &schema.fields,
+            options,
+        )?;
+
+        let mut document = Document::new(options, ctx, 3);
+        document.anchor_prefix = Some(anchor.clone());
+        document.used_anchors = used_anchors;
+        document.write_root(&schema.fields).unwrap();
+        for field in &schema.fields {
+            document.write_section(field, 0, &[]).unwrap();
+        }
+        used_anchors = std::mem::take(&mut document.used_anchors);
+
+        if options.toc != TableOfContents::None {
+            writeln!(body, "<a id=\"{}\"></a>", anchor).unwrap();
+            toc.push(TocEntry {
+                depth: 0,
+                label: name.to_string(),
+                anchor,
+            });
+        }
+        writeln!(body, "## {}", name).unwrap();
+        writeln!(body).unwrap();
+
+        toc.extend(document.toc.into_iter().map(|entry| TocEntry {
+            depth: entry.depth + 1,
+            ..entry
+        }));
+        body.push_str(&document.body);
+    }
+
+    Ok(finish_document(options, &toc, &body))
+}
+
+/// Put a document together from its title, table of contents and body
+fn finish_document(options: &MarkdownOptions, toc: &[TocEntry], body: &str) -> String {
+    let mut buffer = String::new();
+
+    if let Some(title) = &options.title {
+        writeln!(buffer, "# {}", title).unwrap();
+        writeln!(buffer).unwrap();
+    }
+
+    if !toc.is_empty() {
+        write_toc(&mut buffer, toc, "").unwrap();
+        writeln!(buffer).unwrap();
+    }
+
+    buffer.push_str(body);
+    buffer
+}
+
+/// Generate one Markdown page per top-level section of a schema, plus an
+/// `index.md` page linking to them, keyed by file name
+pub(crate) fn schema_pages(
+    name: Option<&str>,
+    env_prefix: Option<&str>,
+    fields: &[FieldInfo],
+    options: &MarkdownOptions,
+) -> Result<BTreeMap<String, String>, ShapeError> {
+    let ctx = context(name, env_prefix, fields, options)?;
+
+    let mut pages = BTreeMap::new();
+    let mut index = String::new();
+
+    if let Some(title) = &options.title {
+        writeln!(index, "# {}", title).unwrap();
+        writeln!(index).unwrap();
+    }
+
+    // The root's own keys stay on the index page
+    let mut root = Document::new(options, ctx, 2);
+    root.write_root(fields).unwrap();
+    write_toc(&mut index, &root.toc, "").unwrap();
+
+    // Page names are lowercase and `index` is taken, so sections named
+    // `index` or differing only by case get a suffix instead of overwriting
+    // each other, even on case-insensitive file systems
+    let mut used = HashSet::from(["index".to_string()]);
+    for field in fields.iter().filter(|f| f.is_nested) {
+        let file_name = format!("{}.md", unique_anchor(&mut used, slug(&[&field.name])));
+
+        // Each page's section is its title, one level above the sections
+        // of a single document
+        let mut page = Document::new(options, ctx, 1);
+        page.write_section(field, 0, &[]).unwrap();
+
+        let summary = field.doc_comments.as_deref().map(paragraphs);
+        match summary.as_ref().and_then(|summary| summary.first()) {
+            Some(summary) => writeln!(
+                index,
+                "- [{}]({}): {}",
+                capitalize(&field.name),
+                file_name,
+                summary
+            ),
+            None => writeln!(index, "- [{}]({})", capitalize(&field.name), file_name),
+        }
+        .unwrap();
+
+        // The page's own entry is the link above
+        let nested = page.toc.get(1..).unwrap_or_default();
+        write_toc(&mut index, nested, &file_name).unwrap();
+
+        pages.insert(file_name, page.body);
+    }
+
+    if !root.body.is_empty() {
+        writeln!(index).unwrap();
+        index.push_str(&root.body);
+    }
+
+    pages.insert("index.md".to_string(), index);
+    Ok(pages)
+}
+
+/// Check that a schema can be written in the chosen format, and set up the
+/// context to write it in
+fn context<'a>(
+    name: Option<&'a str>,
+    env_prefix: Option<&'a str>,
+    fields: &[FieldInfo],
+    options: &MarkdownOptions,
+) -> Result<RenderContext<'a>, ShapeError> {
+    options.format.renderer().validate(fields)?;
+
+    Ok(RenderContext {
+        name,
+        env_prefix,
+        defaults: options.defaults,
+    })
+}
+
+/// Write table of contents entries as a nested list, linking to anchors in
+/// the page `file`, or in the same page if it's empty
+fn write_toc(buffer: &mut String, toc: &[TocEntry], file: &str) -> fmt::Result {
+    for entry in toc {
+        let indent = "  ".repeat(entry.depth);
+        writeln!(
+            buffer,
+            "{}- [{}]({}#{})",
+            indent, entry.label, file, entry.anchor
+        )?;
+    }
+    Ok(())
+}
+
+/// A link in the table of contents
+struct TocEntry {
+    depth: usize,
+    label: String,
+    anchor: String,
+}
+
+/// The sections of a Markdown document as they're written, along with its
+/// table of contents
+struct Document<'a> {
+    options: &'a MarkdownOptions,
+    ctx: RenderContext<'a>,
+    /// The heading level of top-level sections
+    level: usize,
+    /// Goes in front of every anchor, to tell documents on the same page apart
+    anchor_prefix: Option<String>,
+    /// The anchor handed out for each path, with the root section at `[]`
+    anchors: HashMap<Vec<String>, String>,
+    /// Every anchor on the page, including those of other documents on it
+    used_anchors: HashSet<String>,
+    body: String,
+    toc: Vec<TocEntry>,
+}
+
+impl<'a> Document<'a> {
+    fn new(options: &'a MarkdownOptions, ctx: RenderContext<'a>, level: usize) -> Self {
+        Document {
+            options,
+            ctx,
+            level,
+            anchor_prefix: None,
+            anchors: HashMap::new(),
+            used_anchors: HashSet::new(),
+            body: String::new(),
+            toc: Vec::new(),
+        }
+    }
+
+    /// Write a section documenting the keys at the root of the config, if any
+    ///
+    /// It comes before every other section, since in formats like TOML root
+    /// keys have to be written before the first section header.
+    fn write_root(&mut self, fields: &[FieldInfo]) -> fmt::Result {
+        let leaves: Vec<FieldInfo> = fields.iter().filter(|f| !f.is_nested).cloned().collect();
+        if leaves.is_empty() {
+            return Ok(());
+        }
+
+        let options = self.options;
+        let renderer = options.format.renderer();
+        let anchor = self.anchor(&[]);
+        self.write_heading(0, &options.root_title, &anchor)?;
+
+        if options.layout != MarkdownLayout::Table {
+            // A document holding only the root keys shows them where they belong
+            let mut document = String::new();
+            renderer.write_document(&mut document, &self.ctx, &leaves)?;
+
+            writeln!(self.body, "```{}", renderer.code_fence())?;
+            writeln!(self.body, "{}", document.trim_end())?;
+            writeln!(self.body, "```")?;
+        }
+        let show_table =
+            options.layout != MarkdownLayout::Examples || renderer.comment_marker().is_none();
+        if show_table {
+            writeln!(self.body)?;
+            self.write_reference_table(&leaves, &[], false)?;
+        }
+        self.push_key_entries(&leaves, &[], false, show_table, &anchor);
+
+        writeln!(self.body)
+    }
+
+    /// Write documentation for a field and its nested fields
+    fn write_section(&mut self, field: &FieldInfo, depth: usize, path: &[&str]) -> fmt::Result {
+        if !field.is_nested {
+            return Ok(());
+        }
+
+        let options = self.options;
+        let renderer = options.format.renderer();
+
+        let mut current_path = path.to_vec();
+        current_path.push(&field.name);
+
+        // Top-level sections are titled by their capitalized name, e.g.
+        // `Server`, and nested ones by their full path as written in the
+        // config, e.g. `server.tls`
+        let section_name = match current_path.as_slice() {
+            [name] => capitalize(name),
+            path => format!("`{}`", path.join(".")),
+        };
+        let anchor = self.anchor(&current_path);
+        self.write_heading(depth, &section_name, &anchor)?;
+
+        if let Some(doc) = &field.doc_comments {
+            writeln!(self.body)?;
+            writeln!(self.body, "{}", doc)?;
+        }
+
+        let show_example = options.layout != MarkdownLayout::Table;
+        let show_table =
+            options.layout != MarkdownLayout::Examples || renderer.comment_marker().is_none();
+        // The example holds the nested sections of formats that nest them, so
+        // they aren't documented on their own
+        let nests_sections = show_example && renderer.nests_sections();
+        let fields = &field.nested_fields;
+
+        if show_example {
+            renderer.write_section_docs(&mut self.body, &self.ctx, field, &current_path)?;
+        }
+        if show_table {
+            writeln!(self.body)?;
+            self.write_reference_table(fields, &current_path, nests_sections)?;
+        }
+        self.push_key_entries(fields, &current_path, nests_sections, show_table, &anchor);
+
+        writeln!(self.body)?;
+
+        if nests_sections {
+            return Ok(());
+        }
+
+        // Recursively document nested fields
+        for nested_field in fields {
+            self.write_section(nested_field, depth + 1, &current_path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a section's heading, `depth` levels below the top, and add it to
+    /// the table of contents
+    ///
+    /// Each level of nesting adds one more `#` to the heading of top-level
+    /// sections, up to Markdown's limit of six.
+    fn write_heading(&mut self, depth: usize, title: &str, anchor: &str) -> fmt::Result {
+        if self.options.toc != TableOfContents::None {
+            // An explicit anchor stays the same if the heading's text changes
+            writeln!(self.body, "<a id=\"{}\"></a>", anchor)?;
+            self.toc.push(TocEntry {
+                depth,
+                label: title.to_string(),
+                anchor: anchor.to_string(),
+            });
+        }
+
+        let level = "#".repeat((depth + self.level).min(6));
+        writeln!(self.body, "{} {}", level, title)
+    }
+
+    /// Add the keys of the section at `path` to the table of contents, linking
+    /// to their table rows if `show_table` is set and to the section otherwise
+    fn push_key_entries(
+        &mut self,
+        fields: &[FieldInfo],
+        path: &[&str],
+        nested: bool,
+        show_table: bool,
+        section_anchor: &str,
+    ) {
+        if self.options.toc != TableOfContents::Keys {
+            return;
+        }
+        // Keys are listed right below their section's entry
+        let depth = self.toc.last().map_or(0, |section| section.depth + 1);
+
+        let mut keys = Vec::new();
+        collect_keys(fields, &[], nested, &mut keys);
+
+        for (key, _) in keys {
+            let anchor = if show_table {
+                let mut full_path = path.to_vec();
+                full_path.extend(&key);
+                self.anchor(&full_path)
+            } else {
+                section_anchor.to_string()
+            };
+
+            self.toc.push(TocEntry {
+                depth,
+                label: format!("`{}`", key.join(".")),
+                anchor,
+            });
+        }
+    }
+
+    /// Write a table documenting each key in `fields` of the section at
+    /// `path`, including the keys of nested sections if `nested` is set
+    fn write_reference_table(
+        &mut self,
+        fields: &[FieldInfo],
+        path: &[&str],
+        nested: bool,
+    ) -> fmt::Result {
+        let mut keys = Vec::new();
+        collect_keys(fields, &[], nested, &mut keys);
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        let renderer = self.options.format.renderer();
+        writeln!(
+            self.body,
+            "| Key | Type | Default | Env var | Description |"
+        )?;
+        writeln!(self.body, "| --- | --- | --- | --- | --- |")?;
+
+        for (key, field) in keys {
+            let mut full_path = path.to_vec();
+            full_path.extend(&key);
+
+            let field_type = match field.field_type.as_str() {
+                "" => String::new(),
+                field_type => code_span(field_type),
+            };
+            let default = match field.default_value.as_ref().filter(|v| !v.is_null()) {
+                Some(default) => code_span(&renderer.render_value(default)),
+                None if field.required => "*required*".to_string(),
+                None => String::new(),
+            };
+            let description = field
+                .doc_comments
+                .as_deref()
+                .map(table_text)
+                .unwrap_or_default();
+            let mut key_cell = code_span(&key.join("."));
+            if self.options.toc == TableOfContents::Keys {
+                key_cell = format!("<a id=\"{}\"></a>{}", self.anchor(&full_path), key_cell);
+            }
+
+            writeln!(
+                self.body,
+                "| {} | {} | {} | {} | {} |",
+                key_cell,
+                field_type,
+                default,
+                code_span(&self.ctx.env_var(&full_path)),
+                description
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// The anchor of the section or key at `path`, behind the document's
+    /// prefix if it has one, or of the root section if `path` is empty
+    ///
+    /// Different paths can slug to the same text, e.g. `a.b-c` and `a-b.c`,
+    /// so later ones get a `-1`, `-2`... suffix the way GitHub numbers
+    /// repeated headings. Asking again for the same path gives the same anchor.
+    fn anchor(&mut self, path: &[&str]) -> String {
+        let key: Vec<String> = path.iter().map(|segment| segment.to_string()).collect();
+        if let Some(anchor) = self.anchors.get(&key) {
+            return anchor.clone();
+        }
+
+        let mut segments: Vec<&str> = self.anchor_prefix.as_deref().into_iter().collect();
+        if path.is_empty() {
+            segments.push(&self.options.root_title);
+        } else {
+            segments.extend(path);
+        }
+        let anchor = unique_anchor(&mut self.used_anchors, slug(&segments));
+
+        self.anchors.insert(key, anchor.clone());
+        anchor
+    }
+}
+
+/// Collect the leaf fields below `fields` with their paths relative to the
+/// section, descending into nested sections if `nested` is set
+fn collect_keys<'a>(
+    fields: &'a [FieldInfo],
+    path: &[&'a str],
+    nested: bool,
+    keys: &mut Vec<(Vec<&'a str>, &'a FieldInfo)>,
+) {
+    for field in fields {
+        let mut key = path.to_vec();
+        key.push(&field.name);
+
+        if !field.is_nested {
+            keys.push((key, field));
+        } else if nested {
+            collect_keys(&field.nested_fields, &key, nested, keys);
+        }
+    }
+}
+
+/// Write text as a code span that fits in a table cell
+fn code_span(text: &str) -> String {
+    let text = text.replace('|', "\\|");
+
+    // A backtick inside the span needs a longer fence around it
+    if text.contains('`') {
+        format!("`` {} ``", text)
+    } else {
+        format!("`{}`", text)
+    }
+}
+
+/// Fit a doc comment into a single table cell, keeping paragraph breaks
+fn table_text(doc: &str) -> String {
+    paragraphs(doc).join("<br><br>").replace('|', "\\|")
+}
+
+/// Split a doc comment into paragraphs, each joined into a single line
+fn paragraphs(doc: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut lines = Vec::new();
+
+    for line in doc.lines().map(str::trim) {
+        if line.is_empty() {
+            if !lines.is_empty() {
+                paragraphs.push(lines.join(" "));
+                lines.clear();
+            }
+        } else {
+            lines.push(line);
+        }
+    }
+    if !lines.is_empty() {
+        paragraphs.push(lines.join(" "));
+    }
+
+    paragraphs
+}
+
+/// A GitHub style anchor for the section or key at `path`, lowercase with
+/// punctuation dropped and the path's segments joined by dashes, e.g.
+/// `server-tls` for `server.tls`
+fn slug(path: &[&str]) -> String {
+    let segments: Vec<String> = path
+        .iter()
+        .map(|segment| {
+            segment
+                .to_lowercase()
+                .chars()
+                .filter_map(|c| match c {
+                    ' ' => Some('-'),
+                    c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+
+    segments.join("-")
+}
+
+/// Reserve `anchor`, or the first of `anchor-1`, `anchor-2`... that isn't
+/// taken yet
+fn unique_anchor(used: &mut HashSet<String>, anchor: String) -> String {
+    let mut unique = anchor.clone();
+    let mut suffix = 1;
+    while !used.insert(unique.clone()) {
+        unique = format!("{}-{}", anchor, suffix);
+        suffix += 1;
+    }
+    unique
+}
+
+/// Capitalize the first letter of a string
+fn capitalize(s: &str) -> String {
+    let mut c = s.chars();
+    match c.next() {
+        None => String::new(),
+        Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Renderer, Value};
+
+    /// A format that can't write any schema
+    #[derive(Debug)]
+    struct Unwritable;
+
+    impl Renderer for Unwritable {
+        fn name(&self) -> &str {
+            "unwritable"
+        }
+        fn extension(&self) -> &str {
+            "txt"
+        }
+        fn comment_marker(&self) -> Option<&str> {
+            None
+        }
+        fn render_value(&self, _value: &Value) -> String {
+            String::new()
+        }
+        fn render_document(&self, _value: &Value) -> Result<String, ShapeError> {
+            Ok(String::new())
+        }
+        fn validate(&self, fields: &[FieldInfo]) -> Result<(), ShapeError> {
+            Err(ShapeError {
+                format: ConfigFormat::Custom(&Unwritable),
+                path: fields[0].name.clone(),
+                reason: "nothing can be written".to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn unwritable_schema_is_an_error() {
+        let fields = [FieldInfo::new("port").field_type("u16")];
+        let options = MarkdownOptions::new(ConfigFormat::Custom(&Unwritable));
+
+        let err = try_generate_markdown(&fields, &options).unwrap_err();
+        assert_eq!(err.path, "port");
+        assert!(try_generate_markdown_pages(&fields, &options).is_err());
+
+        let schema = ConfigSchema::builder()
+            .name("Config")
+            .add_field(fields[0].clone())
+            .build();
+        assert!(try_generate_combined_docs(&[schema], &options).is_err());
+    }
+
+    #[cfg(feature = "ini")]
+    #[test]
+    fn list_of_tables_is_an_error_in_ini() {
+        let server = Value::Table(vec![("host".to_string(), Value::String("a".to_string()))]);
+        let fields = [FieldInfo::new("servers")
+            .field_type("Vec<Server>")
+            .default(Value::Array(vec![server]))];
+
+        let err =
+            try_generate_markdown(&fields, &MarkdownOptions::new(ConfigFormat::Ini)).unwrap_err();
+        assert_eq!(err.path, "servers");
+    }
+
+    /// A schema with root keys, a section and a nested section
+    #[cfg(feature = "toml")]
+    fn schema() -> ConfigSchema {
+        ConfigSchema::builder()
+            .name("Config")
+            .env_prefix("APP")
+            .add_field(
+                FieldInfo::new("port")
+                    .doc("Port to listen on")
+                    .field_type("u16")
+                    .default(Value::Integer(8080)),
+            )
+            .add_field(
+                FieldInfo::new("name")
+                    .doc("Name shown in logs | traces")
+                    .field_type("Option<String>")
+                    .example(Value::from("app")),
+            )
+            .add_field(FieldInfo::new("server").doc("Server settings").nested(vec![
+                FieldInfo::new("host")
+                    .field_type("String")
+                    .default(Value::from("localhost")),
+                FieldInfo::new("tls").nested(vec![FieldInfo::new("cert")
+                    .doc("Certificate\n\nPEM encoded")
+                    .field_type("String")
+                    .required(true)]),
+            ]))
+            .build()
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn table_layout_lists_every_key() {
+        let options = MarkdownOptions::new(ConfigFormat::Toml).layout(MarkdownLayout::Table);
+        let docs = schema().generate_docs_with_options(&options);
+        assert_eq!(
+            docs,
+            r#"## General
+
+| Key | Type | Default | Env var | Description |
+| --- | --- | --- | --- | --- |
+| `port` | `u16` | `8080` | `APP_PORT` | Port to listen on |
+| `name` | `Option<String>` |  | `APP_NAME` | Name shown in logs \| traces |
+
+## Server
+
+Server settings
+
+| Key | Type | Default | Env var | Description |
+| --- | --- | --- | --- | --- |
+| `host` | `String` | `"localhost"` | `APP_SERVER__HOST` |  |
+
+### `server.tls`
+
+| Key | Type | Default | Env var | Description |
+| --- | --- | --- | --- | --- |
+| `cert` | `String` | *required* | `APP_SERVER__TLS__CERT` | Certificate<br><br>PEM encoded |
+
+"#
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn both_layout_shows_the_example_then_the_table() {
+        let options = MarkdownOptions::new(ConfigFormat::Toml).layout(MarkdownLayout::Both);
+        let docs = schema().generate_docs_with_options(&options);
+
+        let example = docs.find("```toml\n[server]\n").unwrap();
+        let table = docs.find("| `host` |").unwrap();
+        let next = docs.find("## `server.tls`").unwrap();
+        assert!(example < table && table < next, "{}", docs);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn examples_without_comments_get_the_table_too() {
+        let fields = [FieldInfo::new("port")
+            .doc("Port to listen on")
+            .field_type("u16")
+            .default(Value::Integer(8080))];
+        let docs = generate_markdown(&fields, &MarkdownOptions::new(ConfigFormat::Json));
+        assert!(docs.contains("```json\n"), "{}", docs);
+        assert!(docs.contains("| `port` | `u16` | `8080` |"), "{}", docs);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn headings_follow_nesting_depth() {
+        // Seven levels deep, so the innermost sections go past `######`
+        let fields = ["a", "b", "c", "d", "e", "f"].iter().rev().fold(
+            vec![FieldInfo::new("key").field_type("u8")],
+            |nested, name| vec![FieldInfo::new(*name).nested(nested)],
+        );
+
+        let docs = generate_markdown(&fields, &MarkdownOptions::new(ConfigFormat::Toml));
+        let headings: Vec<&str> = docs.lines().filter(|l| l.starts_with("##")).collect();
+        assert_eq!(
+            headings,
+            [
+                "## A",
+                "### `a.b`",
+                "#### `a.b.c`",
+                "##### `a.b.c.d`",
+                "###### `a.b.c.d.e`",
+                "###### `a.b.c.d.e.f`",
+            ]
+        );
+        // Snippets show the full path, as TOML needs it
+        assert!(docs.contains("```toml\n[a.b.c.d.e.f]\n"), "{}", docs);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn root_keys_get_a_section_of_their_own() {
+        let options = MarkdownOptions::new(ConfigFormat::Toml).root_title("Top level");
+        let docs = schema().generate_docs_with_options(&options);
+        assert!(
+            docs.starts_with(
+                "\
+## Top level
+```toml
+# Port to listen on
+# Default: 8080
+port = 8080
+
+"
+            ),
+            "{}",
+            docs
+        );
+        assert!(docs.find("name = \"app\"").unwrap() < docs.find("## Server").unwrap());
+
+        // Without root keys there's no root section
+        let fields =
+            [FieldInfo::new("server").nested(vec![FieldInfo::new("host").field_type("String")])];
+        let docs = generate_markdown(&fields, &options);
+        assert!(docs.starts_with("## Server\n"), "{}", docs);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn table_of_contents_links_to_each_section() {
+        let options = MarkdownOptions::new(ConfigFormat::Toml).toc(TableOfContents::Sections);
+        let docs = schema().generate_docs_with_options(&options);
+        assert!(
+            docs.starts_with(
+                "\
+- [General](#general)
+- [Server](#server)
+  - [`server.tls`](#server-tls)
+
+<a id=\"general\"></a>
+## General
+"
+            ),
+            "{}",
+            docs
+        );
+
+        let options = options.toc(TableOfContents::Keys);
+        let docs = schema().generate_docs_with_options(&options);
+        // Keys link to their section, since they only have an example
+        assert!(
+            docs.starts_with(
+                "\
+- [General](#general)
+  - [`port`](#general)
+  - [`name`](#general)
+- [Server](#server)
+  - [`host`](#server)
+  - [`server.tls`](#server-tls)
+    - [`cert`](#server-tls)
+
+"
+            ),
+            "{}",
+            docs
+        );
+
+        // and to their row once there's a table
+        let options = options.layout(MarkdownLayout::Table);
+        let docs = schema().generate_docs_with_options(&options);
+        assert!(
+            docs.contains("    - [`cert`](#server-tls-cert)\n"),
+            "{}",
+            docs
+        );
+        assert!(
+            docs.contains("| <a id=\"server-tls-cert\"></a>`cert` |"),
+            "{}",
+            docs
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn pages_split_docs_by_top_level_section() {
+        let options = MarkdownOptions::new(ConfigFormat::Toml)
+            .title(Some("Settings".to_string()))
+            .toc(TableOfContents::Sections);
+        let pages = schema().generate_doc_pages(&options);
+        assert_eq!(pages.keys().collect::<Vec<_>>(), ["index.md", "server.md"]);
+        assert_eq!(
+            pages["index.md"],
+            r#"# Settings
+
+- [General](#general)
+- [Server](server.md): Server settings
+  - [`server.tls`](server.md#server-tls)
+
+<a id="general"></a>
+## General
+```toml
+# Port to listen on
+# Default: 8080
+port = 8080
+
+# Name shown in logs | traces
+# Optional
+name = "app"
+```
+
+"#
+        );
+        // Each page's section is its title
+        assert!(
+            pages["server.md"].starts_with("<a id=\"server\"></a>\n# Server\n\nServer settings\n"),
+            "{}",
+            pages["server.md"]
+        );
+        assert!(pages["server.md"].contains("<a id=\"server-tls\"></a>\n## `server.tls`\n"));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn index_links_show_the_first_paragraph() {
+        let fields = [
+            FieldInfo::new("log")
+                .doc("Logging\n\nLevels and targets")
+                .nested(vec![FieldInfo::new("level").field_type("String")]),
+            FieldInfo::new("cache").nested(vec![FieldInfo::new("size").field_type("u64")]),
+        ];
+        let pages = generate_markdown_pages(&fields, &MarkdownOptions::new(ConfigFormat::Toml));
+        assert_eq!(
+            pages["index.md"],
+            "- [Log](log.md): Logging\n- [Cache](cache.md)\n"
+        );
+        assert!(pages["log.md"].starts_with("# Log\n\nLogging\n\nLevels and targets\n"));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn page_names_never_collide() {
+        let section = |name| FieldInfo::new(name).nested(vec![FieldInfo::new("size")]);
+        let fields = [section("index"), section("Cache"), section("cache")];
+        let pages = generate_markdown_pages(&fields, &MarkdownOptions::new(ConfigFormat::Toml));
+        assert_eq!(
+            pages.keys().collect::<Vec<_>>(),
+            ["cache-1.md", "cache.md", "index-1.md", "index.md"]
+        );
+        assert_eq!(
+            pages["index.md"],
+            "- [Index](index-1.md)\n- [Cache](cache.md)\n- [Cache](cache-1.md)\n"
+        );
+        assert!(
+            pages["cache-1.md"].contains("[cache]"),
+            "{}",
+            pages["cache-1.md"]
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn combined_docs_put_each_schema_under_its_name() {
+        let worker = ConfigSchema::builder()
+            .name("Worker")
+            .add_field(
+                FieldInfo::new("server").nested(vec![FieldInfo::new("threads")
+                    .field_type("u8")
+                    .default(Value::Integer(4))]),
+            )
+            .build();
+        let schema = schema();
+        let options = MarkdownOptions::new(ConfigFormat::Toml)
+            .title(Some("Settings".to_string()))
+            .toc(TableOfContents::Sections);
+
+        let docs = combined_markdown(
+            &[("my_app::Config", &schema), ("Worker", &worker)],
+            &options,
+        )
+        .unwrap();
+        assert!(
+            docs.starts_with(
+                r#"# Settings
+
+- [my_app::Config](#my_app-config)
+  - [General](#my_app-config-general)
+  - [Server](#my_app-config-server)
+    - [`server.tls`](#my_app-config-server-tls)
+- [Worker](#worker)
+  - [Server](#worker-server)
+
+<a id="my_app-config"></a>
+## my_app::Config
+
+<a id="my_app-config-general"></a>
+### General
+"#
+            ),
+            "{}",
+            docs
+        );
+        // Sections are one level below their schema
+        assert!(docs.contains("\n#### `server.tls`\n"));
+        assert!(docs.contains(
+            "<a id=\"worker\"></a>\n## Worker\n\n<a id=\"worker-server\"></a>\n### Server\n"
+        ));
+    }
+
+    /// The anchors defined in a document, in order
+    #[cfg(feature = "toml")]
+    fn anchors(docs: &str) -> Vec<&str> {
+        docs.split("<a id=\"")
+            .skip(1)
+            .map(|rest| &rest[..rest.find('"').unwrap()])
+            .collect()
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn colliding_anchors_get_a_suffix() {
+        let fields = [
+            FieldInfo::new("port").field_type("u16"),
+            FieldInfo::new("general").nested(vec![FieldInfo::new("name").field_type("String")]),
+            FieldInfo::new("a").nested(vec![FieldInfo::new("b-c").field_type("u8")]),
+            FieldInfo::new("a-b").nested(vec![FieldInfo::new("c").field_type("u8")]),
+        ];
+        let options = MarkdownOptions::new(ConfigFormat::Toml)
+            .layout(MarkdownLayout::Table)
+            .toc(TableOfContents::Keys);
+
+        let docs = generate_markdown(&fields, &options);
+        assert_eq!(
+            anchors(&docs),
+            [
+                "general",
+                "port",
+                "general-1",
+                "general-name",
+                "a",
+                "a-b-c",
+                "a-b",
+                "a-b-c-1",
+            ]
+        );
+        // The table of contents links to the same anchors as the headings and rows
+        assert!(docs.contains("- [General](#general)\n"));
+        assert!(docs.contains("- [General](#general-1)\n"));
+        assert!(docs.contains("- [`b-c`](#a-b-c)\n"));
+        assert!(docs.contains("- [`c`](#a-b-c-1)\n"));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn combined_docs_keep_anchors_unique_across_schemas() {
+        let schema = |name: &str, key: &str| {
+            ConfigSchema::builder()
+                .name(name)
+                .add_field(FieldInfo::new(key).field_type("u8"))
+                .build()
+        };
+        let schemas = [schema("Config", "port"), schema("Config", "port")];
+        let options = MarkdownOptions::new(ConfigFormat::Toml).layout(MarkdownLayout::Table);
+
+        let docs = generate_combined_docs(&schemas, &options);
+        let anchors = anchors(&docs);
+        let unique: HashSet<_> = anchors.iter().collect();
+        assert_eq!(anchors.len(), unique.len(), "{:?}", anchors);
+    }
+}
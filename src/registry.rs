@@ -0,0 +1,176 @@
+// serde_config_docs/src/registry.rs
+//! A registry of every documented root type linked into the program
+//!
+//! With the `registry` feature, each struct deriving `ConfigDocs` with
+//! `#[config_docs(export)]`, `inject` or `root` registers itself at link
+//! time, so one test can document the config types of a whole workspace.
+
+use crate::markdown::combined_markdown;
+use crate::{
+    update_docs_file, ConfigSchema, DocsError, DocsMode, ExportSettings, MarkdownOptions,
+    ShapeError,
+};
+
+use std::path::{Path, PathBuf};
+
+/// A documented root type, as registered by the derive macro
+#[derive(Debug)]
+pub struct RegisteredConfig {
+    /// The name of the type
+    pub name: &'static str,
+    /// The module the type is defined in, e.g. `my_crate::settings`
+    pub module_path: &'static str,
+    /// Returns the type's schema
+    pub schema: fn() -> ConfigSchema,
+    /// The directory of the crate the type is defined in, which paths in its
+    /// export settings are relative to
+    pub manifest_dir: &'static str,
+    /// How the type's own `export` and `inject` attributes export its docs,
+    /// if it has any
+    pub export: Option<ExportSettings>,
+}
+
+impl RegisteredConfig {
+    /// The full path of the type, e.g. `my_crate::settings::Config`
+    pub fn path(&self) -> String {
+        format!("{}::{}", self.module_path, self.name)
+    }
+}
+
+inventory::collect!(RegisteredConfig);
+
+/// Every registered root type, sorted by path
+///
+/// Only types in crates that are linked into the running program are found,
+/// so a test enumerating a workspace's configs belongs in a crate that
+/// depends on all of them.
+pub fn registered_configs() -> Vec<&'static RegisteredConfig> {
+    let mut configs: Vec<_> = inventory::iter::<RegisteredConfig>.into_iter().collect();
+    configs.sort_by_key(|config| config.path());
+    configs
+}
+
+/// Generate docs for every registered root type in one document, each under
+/// a heading with its full path
+///
+/// # Panics
+///
+/// Panics if a type holds a value the format can't express. Use
+/// [`try_generate_registered_docs`] to get a [`ShapeError`] instead.
+pub fn generate_registered_docs(options: &MarkdownOptions) -> String {
+    try_generate_registered_docs(options).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Generate docs for every registered root type in one document, or fail if
+/// the format can't express one of them
+pub fn try_generate_registered_docs(options: &MarkdownOptions) -> Result<String, ShapeError> {
+    let configs: Vec<(String, ConfigSchema)> = registered_configs()
+        .iter()
+        .map(|config| (config.path(), (config.schema)()))
+        .collect();
+    let schemas: Vec<(&str, &ConfigSchema)> = configs
+        .iter()
+        .map(|(path, schema)| (path.as_str(), schema))
+        .collect();
+    combined_markdown(&schemas, options)
+}
+
+/// Write docs for every registered root type to `dir`, or check them in
+/// check mode, and return the paths of the files
+///
+/// Each type gets a file named after its full path, e.g.
+/// `my_crate.settings.Config.toml.md`, so types that share a name in
+/// different crates don't clash.
+pub fn export_registered_docs(
+    dir: impl AsRef<Path>,
+    options: &MarkdownOptions,
+    mode: DocsMode,
+) -> Result<Vec<PathBuf>, DocsError> {
+    let mut paths = Vec::new();
+
+    for config in registered_configs() {
+        let file_name = format!(
+            "{}.{}.md",
+            config.path().replace("::", "."),
+            options.format.extension()
+        );
+        let path = dir.as_ref().join(file_name);
+
+        let docs = (config.schema)()
+            .try_generate_docs_with_options(options)
+            .map_err(DocsError::Shape)?;
+        update_docs_file(&path, &docs, mode)?;
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+#[cfg(all(test, feature = "toml"))]
+mod tests {
+    use super::*;
+    use crate::{ConfigFormat, FieldInfo};
+
+    use std::fs;
+
+    fn schema() -> ConfigSchema {
+        ConfigSchema::builder()
+            .name("Config")
+            .add_field(FieldInfo::new("port").field_type("u16"))
+            .build()
+    }
+
+    inventory::submit! {
+        RegisteredConfig {
+            name: "Config",
+            module_path: "server",
+            schema,
+            manifest_dir: "",
+            export: None,
+        }
+    }
+
+    inventory::submit! {
+        RegisteredConfig {
+            name: "Config",
+            module_path: "client",
+            schema,
+            manifest_dir: "",
+            export: None,
+        }
+    }
+
+    #[test]
+    fn configs_are_sorted_by_path() {
+        let paths: Vec<String> = registered_configs().iter().map(|c| c.path()).collect();
+        assert_eq!(paths, ["client::Config", "server::Config"]);
+    }
+
+    #[test]
+    fn types_sharing_a_name_get_their_own_heading() {
+        let docs = generate_registered_docs(&MarkdownOptions::new(ConfigFormat::Toml));
+        let client = docs.find("## client::Config\n").unwrap();
+        let server = docs.find("## server::Config\n").unwrap();
+        assert!(client < server, "{}", docs);
+    }
+
+    #[test]
+    fn exported_files_are_named_after_the_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = MarkdownOptions::new(ConfigFormat::Toml);
+
+        let paths = export_registered_docs(dir.path(), &options, DocsMode::Write).unwrap();
+        assert_eq!(
+            paths,
+            [
+                dir.path().join("client.Config.toml.md"),
+                dir.path().join("server.Config.toml.md"),
+            ]
+        );
+        export_registered_docs(dir.path(), &options, DocsMode::Check).unwrap();
+
+        fs::write(&paths[1], "stale").unwrap();
+        let err = export_registered_docs(dir.path(), &options, DocsMode::Check).unwrap_err();
+        assert!(matches!(err, DocsError::Stale { path, .. } if path == paths[1]));
+    }
+}
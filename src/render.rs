@@ -0,0 +1,498 @@
+// serde_config_docs/src/render.rs
+//! The [`Renderer`] trait, which describes how an output format is written
+//!
+//! A renderer prints comments, section headers, keys and values. Its provided
+//! methods combine those into documents the way INI and TOML lay them out,
+//! with a section's keys first and a `[section]` header above each nested
+//! section, so a format in that style only fills in the pieces. Formats that
+//! nest sections or have no comments override the layout methods instead.
+//!
+//! Built-in formats are renderers as well, and [`ConfigFormat::Custom`] or
+//! [`ConfigFormat::register`] plug in new ones.
+
+use crate::example::placeholder_value;
+use crate::{
+    env_var_name, ConfigFormat, ConfigSchema, DefaultsStyle, FieldInfo, ShapeError, Value,
+};
+
+use std::fmt::{self, Write};
+use std::sync::RwLock;
+
+/// Describes how to write and read one config file format
+///
+/// Only the format's identity and how it renders values have to be
+/// implemented. See the [module docs](self) for the default layout.
+pub trait Renderer: fmt::Debug + Send + Sync {
+    /// The name of the format, as used in `CONFIG_DOCS_FORMAT`
+    fn name(&self) -> &str;
+
+    /// Other names the format can be looked up by, e.g. `yml` for YAML
+    fn aliases(&self) -> &[&str] {
+        &[]
+    }
+
+    /// The file extension of the format, without a leading dot
+    fn extension(&self) -> &str;
+
+    /// The language of fenced code blocks in Markdown docs
+    fn code_fence(&self) -> &str {
+        self.extension()
+    }
+
+    /// The marker that starts a line comment, or `None` if the format has no
+    /// comments
+    ///
+    /// Keys that are left out of an example are commented out with this
+    /// marker, or omitted entirely without one.
+    fn comment_marker(&self) -> Option<&str>;
+
+    /// Render a value on a single line, as written after a key
+    fn render_value(&self, value: &Value) -> String;
+
+    /// Render a key and its value, e.g. `port = 8080`
+    fn render_entry(&self, key: &str, value: &Value) -> String {
+        format!("{} = {}", key, self.render_value(value))
+    }
+
+    /// Render a whole value as a document, as returned by
+    /// [`ConfigFormat::format`]
+    fn render_document(&self, value: &Value) -> Result<String, ShapeError>;
+
+    /// Whether nested sections are written inside their parent, so Markdown
+    /// docs show them in their parent's snippet rather than in their own
+    fn nests_sections(&self) -> bool {
+        false
+    }
+
+    /// Check that every key and value in a schema can be written
+    fn validate(&self, fields: &[FieldInfo]) -> Result<(), ShapeError> {
+        let _ = fields;
+        Ok(())
+    }
+
+    /// Write the header that opens the section at `path`, e.g. `[server.tls]`
+    fn write_section_header(&self, buffer: &mut String, path: &[&str]) -> fmt::Result {
+        writeln!(buffer, "[{}]", path.join("."))?;
+        writeln!(buffer)
+    }
+
+    /// Write a leaf field at `path` as a documented key
+    fn write_key(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        field: &FieldInfo,
+        path: &[&str],
+    ) -> fmt::Result {
+        let _ = path;
+        let key = ctx.resolve_key(field);
+        let entry = self.render_entry(&field.name, &key.value);
+
+        let Some(marker) = self.comment_marker() else {
+            if key.commented {
+                return Ok(());
+            }
+            return writeln!(buffer, "{}", entry);
+        };
+
+        write_comment_lines(buffer, field.doc_comments.as_deref(), marker)?;
+        write_annotation(buffer, key.annotation.as_ref(), marker, self)?;
+
+        if key.commented {
+            writeln!(buffer, "{} {}", marker, entry)
+        } else {
+            writeln!(buffer, "{}", entry)
+        }
+    }
+
+    /// Write the section at `path` on its own, without its nested sections
+    /// unless the format nests them
+    fn write_section(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        section: &FieldInfo,
+        path: &[&str],
+    ) -> fmt::Result {
+        self.write_section_header(buffer, path)?;
+        write_keys(self, buffer, ctx, &section.nested_fields, path)
+    }
+
+    /// Write a complete example document
+    fn write_document(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        fields: &[FieldInfo],
+    ) -> fmt::Result {
+        write_table(self, buffer, ctx, fields, &[])
+    }
+
+    /// Write the snippet showing the section at `path` in Markdown docs
+    fn write_section_docs(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        section: &FieldInfo,
+        path: &[&str],
+    ) -> fmt::Result {
+        writeln!(buffer, "```{}", self.code_fence())?;
+        self.write_section(buffer, ctx, section, path)?;
+        writeln!(buffer, "```")
+    }
+
+    /// Parse a config file into a value tree
+    ///
+    /// The tree is read with [`from_value`](crate::from_value), which also
+    /// accepts numbers and booleans written as strings. `schema` is the schema
+    /// the file was written for, if known.
+    fn parse(&self, input: &str, schema: Option<&ConfigSchema>) -> Result<Value, String> {
+        let _ = (input, schema);
+        Err(format!("reading {} files isn't supported", self.name()))
+    }
+
+    /// Deserialize a config file, handing `visit` a deserializer for it
+    ///
+    /// By default this reads the value tree returned by [`Renderer::parse`].
+    /// Formats with a serde deserializer of their own can pass that instead,
+    /// which keeps details like enum variant names that a tree can lose.
+    fn deserialize(
+        &self,
+        input: &str,
+        schema: Option<&ConfigSchema>,
+        visit: &mut Visit<'_>,
+    ) -> Result<(), String> {
+        let value = self.parse(input, schema)?;
+        visit_deserializer(visit, value)
+    }
+}
+
+/// Hand a serde deserializer to a [`Visit`] callback, for formats that
+/// implement [`Renderer::deserialize`]
+pub fn visit_deserializer<'de, D: serde::Deserializer<'de>>(
+    visit: &mut Visit<'_>,
+    deserializer: D,
+) -> Result<(), String> {
+    visit(&mut <dyn erased_serde::Deserializer>::erase(deserializer)).map_err(|e| e.to_string())
+}
+
+/// A callback that deserializes a config from a type-erased deserializer, as
+/// passed to [`Renderer::deserialize`]
+pub type Visit<'a> =
+    dyn FnMut(&mut dyn erased_serde::Deserializer<'_>) -> Result<(), erased_serde::Error> + 'a;
+
+/// What a renderer needs to know about the document being written
+#[derive(Debug, Clone, Copy)]
+pub struct RenderContext<'a> {
+    /// The name of the root type, if known
+    pub name: Option<&'a str>,
+    /// The prefix of environment variable names, if any
+    pub env_prefix: Option<&'a str>,
+    pub defaults: DefaultsStyle,
+}
+
+impl RenderContext<'_> {
+    /// Decide what to write for a leaf field
+    pub fn resolve_key(&self, field: &FieldInfo) -> ExampleKey {
+        resolve_key(field, self.defaults)
+    }
+
+    /// The environment variable for the key at `path`
+    pub fn env_var(&self, path: &[&str]) -> String {
+        env_var_name(self.env_prefix, path)
+    }
+}
+
+/// The value to write for a leaf field, and how to annotate it
+#[derive(Debug, Clone)]
+pub struct ExampleKey {
+    pub value: Value,
+    /// Whether the key should be left out of the active config, either by
+    /// commenting it out or by omitting it in formats without comments
+    pub commented: bool,
+    pub annotation: Option<Annotation>,
+}
+
+/// A note written next to an example key
+#[derive(Debug, Clone)]
+pub enum Annotation {
+    Default(Value),
+    Required,
+    Optional,
+}
+
+/// Decide what to write for a leaf field in an example
+pub(crate) fn resolve_key(field: &FieldInfo, defaults: DefaultsStyle) -> ExampleKey {
+    let default = field.default_value.as_ref().filter(|v| !v.is_null());
+    let example = field.example_value.as_ref().filter(|v| !v.is_null());
+
+    match (default, example) {
+        (Some(default), None) => ExampleKey {
+            value: default.clone(),
+            commented: defaults == DefaultsStyle::CommentedOut,
+            annotation: match defaults {
+                DefaultsStyle::Explicit => Some(Annotation::Default(default.clone())),
+                DefaultsStyle::CommentedOut => None,
+            },
+        },
+        (Some(default), Some(example)) => ExampleKey {
+            value: example.clone(),
+            commented: false,
+            annotation: Some(Annotation::Default(default.clone())),
+        },
+        (None, example) => ExampleKey {
+            value: example
+                .cloned()
+                .unwrap_or_else(|| placeholder_value(&field.field_type)),
+            // Optional values without an example are left unset, since not
+            // every format can write a null
+            commented: !field.required && example.is_none(),
+            annotation: Some(if field.required {
+                Annotation::Required
+            } else {
+                Annotation::Optional
+            }),
+        },
+    }
+}
+
+/// Write an example key's annotation as a comment line
+pub fn write_annotation<R: Renderer + ?Sized>(
+    buffer: &mut String,
+    annotation: Option<&Annotation>,
+    marker: &str,
+    renderer: &R,
+) -> fmt::Result {
+    match annotation {
+        Some(Annotation::Default(default)) => {
+            writeln!(
+                buffer,
+                "{} Default: {}",
+                marker,
+                renderer.render_value(default)
+            )
+        }
+        Some(Annotation::Required) => writeln!(buffer, "{} Required", marker),
+        Some(Annotation::Optional) => writeln!(buffer, "{} Optional", marker),
+        None => Ok(()),
+    }
+}
+
+/// Write each line of a doc comment behind a comment marker
+pub fn write_comment_lines(buffer: &mut String, doc: Option<&str>, marker: &str) -> fmt::Result {
+    for line in doc.into_iter().flat_map(str::lines) {
+        if line.is_empty() {
+            writeln!(buffer, "{}", marker)?;
+        } else {
+            writeln!(buffer, "{} {}", marker, line)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write the keys of a table, followed by its sections in nesting order
+fn write_table<R: Renderer + ?Sized>(
+    renderer: &R,
+    buffer: &mut String,
+    ctx: &RenderContext<'_>,
+    fields: &[FieldInfo],
+    path: &[&str],
+) -> fmt::Result {
+    // Keys have to come before any section header, or they'd end up in it
+    write_keys(renderer, buffer, ctx, fields, path)?;
+
+    for field in fields.iter().filter(|f| f.is_nested) {
+        let mut section_path = path.to_vec();
+        section_path.push(&field.name);
+
+        if let Some(marker) = renderer.comment_marker() {
+            write_comment_lines(buffer, field.doc_comments.as_deref(), marker)?;
+        }
+        renderer.write_section_header(buffer, &section_path)?;
+
+        write_table(renderer, buffer, ctx, &field.nested_fields, &section_path)?;
+    }
+
+    Ok(())
+}
+
+/// Write the leaf keys of the section at `path`, each followed by a blank line
+fn write_keys<R: Renderer + ?Sized>(
+    renderer: &R,
+    buffer: &mut String,
+    ctx: &RenderContext<'_>,
+    fields: &[FieldInfo],
+    path: &[&str],
+) -> fmt::Result {
+    for field in fields.iter().filter(|f| !f.is_nested) {
+        let mut field_path = path.to_vec();
+        field_path.push(&field.name);
+
+        renderer.write_key(buffer, ctx, field, &field_path)?;
+        writeln!(buffer)?;
+    }
+
+    Ok(())
+}
+
+/// Join a dotted key path and a key, e.g. `server.tls` and `cert` into
+/// `server.tls.cert`
+#[cfg(any(
+    feature = "ini",
+    feature = "properties",
+    feature = "dotenv",
+    feature = "hcl"
+))]
+pub(crate) fn join_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// Formats added with [`ConfigFormat::register`], newest last
+static REGISTERED: RwLock<Vec<&'static dyn Renderer>> = RwLock::new(Vec::new());
+
+/// Add a format that [`ConfigFormat::from_name`] can find
+pub(crate) fn register(renderer: &'static dyn Renderer) {
+    REGISTERED.write().unwrap().push(renderer);
+}
+
+/// Find a registered format by its name or one of its aliases
+pub(crate) fn find_registered(name: &str) -> Option<ConfigFormat> {
+    REGISTERED
+        .read()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|renderer| matches_name(**renderer, name))
+        .map(|renderer| ConfigFormat::Custom(*renderer))
+}
+
+/// The names of all registered formats
+pub(crate) fn registered_names() -> Vec<String> {
+    REGISTERED
+        .read()
+        .unwrap()
+        .iter()
+        .map(|renderer| renderer.name().to_string())
+        .collect()
+}
+
+/// Whether a renderer goes by `name`, ignoring case
+pub(crate) fn matches_name(renderer: &dyn Renderer, name: &str) -> bool {
+    std::iter::once(renderer.name())
+        .chain(renderer.aliases().iter().copied())
+        .any(|candidate| candidate.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_example, ExampleOptions};
+
+    /// A format in the INI style, with `;` comments
+    #[derive(Debug)]
+    struct Conf;
+
+    impl Renderer for Conf {
+        fn name(&self) -> &str {
+            "test-conf"
+        }
+
+        fn aliases(&self) -> &[&str] {
+            &["test-cfg"]
+        }
+
+        fn extension(&self) -> &str {
+            "conf"
+        }
+
+        fn comment_marker(&self) -> Option<&str> {
+            Some(";")
+        }
+
+        fn render_value(&self, value: &Value) -> String {
+            match value {
+                Value::String(text) => format!("{:?}", text),
+                Value::Integer(i) => i.to_string(),
+                value => format!("{:?}", value),
+            }
+        }
+
+        fn render_document(&self, value: &Value) -> Result<String, ShapeError> {
+            Ok(self.render_value(value))
+        }
+    }
+
+    fn fields() -> Vec<FieldInfo> {
+        vec![
+            FieldInfo::new("port")
+                .doc("Port to listen on")
+                .field_type("u16")
+                .default(Value::Integer(8080))
+                .example(Value::Integer(80)),
+            FieldInfo::new("server").doc("Server settings").nested(vec![
+                FieldInfo::new("host")
+                    .field_type("String")
+                    .default(Value::from("localhost")),
+                FieldInfo::new("tls").nested(vec![FieldInfo::new("cert")
+                    .field_type("String")
+                    .required(true)]),
+            ]),
+            FieldInfo::new("name").field_type("Option<String>"),
+        ]
+    }
+
+    #[test]
+    fn custom_formats_get_the_default_layout() {
+        let format = ConfigFormat::Custom(&Conf);
+        let example = generate_example(&fields(), &ExampleOptions::new(format));
+        assert_eq!(
+            example,
+            "\
+; Port to listen on
+; Default: 8080
+port = 80
+
+; Optional
+; name = \"\"
+
+; Server settings
+[server]
+
+; Default: \"localhost\"
+host = \"localhost\"
+
+[server.tls]
+
+; Required
+cert = \"\"
+"
+        );
+    }
+
+    #[test]
+    fn registered_formats_are_found_by_name_and_alias() {
+        assert!(ConfigFormat::from_name("test-conf").is_none());
+
+        ConfigFormat::register(&Conf);
+        for name in ["test-conf", "TEST-CONF", "test-cfg"] {
+            let format: ConfigFormat = name.parse().unwrap();
+            assert!(matches!(format, ConfigFormat::Custom(_)), "{}", name);
+            assert_eq!(format.extension(), "conf");
+        }
+
+        let err = "test-ini".parse::<ConfigFormat>().unwrap_err();
+        assert!(err.to_string().contains("`test-conf`"), "{}", err);
+    }
+
+    #[test]
+    fn formats_without_a_parser_say_so() {
+        let err = ConfigFormat::Custom(&Conf)
+            .parse::<u16>("port = 80")
+            .unwrap_err();
+        assert_eq!(err, "reading test-conf files isn't supported");
+    }
+}
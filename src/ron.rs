@@ -0,0 +1,308 @@
+// serde_config_docs/src/ron.rs
+//! Rusty Object Notation, as used by a lot of game tooling
+//!
+//! Sections are written as named structs, e.g. `server: Server(port: 8080)`,
+//! and enums use RON's own variant syntax instead of serde's tagged maps.
+
+use crate::render::{
+    resolve_key, visit_deserializer, write_annotation, write_comment_lines, RenderContext,
+    Renderer, Visit,
+};
+use crate::{ConfigFormat, ConfigSchema, DefaultsStyle, FieldInfo, ShapeError, Value, VariantData};
+
+use std::fmt::{self, Write};
+
+/// Lets `Option` fields be written as plain values instead of `Some(...)`,
+/// since values don't remember whether they came from an `Option`
+const HEADER: &str = "#![enable(implicit_some)]";
+
+#[derive(Debug)]
+pub(crate) struct Ron;
+
+impl Renderer for Ron {
+    fn name(&self) -> &str {
+        "ron"
+    }
+
+    fn extension(&self) -> &str {
+        "ron"
+    }
+
+    fn comment_marker(&self) -> Option<&str> {
+        Some("//")
+    }
+
+    fn render_value(&self, value: &Value) -> String {
+        format_value(value)
+    }
+
+    fn render_document(&self, value: &Value) -> Result<String, ShapeError> {
+        ::ron::ser::to_string_pretty(value, Default::default()).map_err(|err| ShapeError {
+            format: ConfigFormat::Ron,
+            path: String::new(),
+            reason: err.to_string(),
+        })
+    }
+
+    fn nests_sections(&self) -> bool {
+        true
+    }
+
+    fn write_section(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        section: &FieldInfo,
+        _path: &[&str],
+    ) -> fmt::Result {
+        write_section(buffer, section, 0, ctx.defaults)
+    }
+
+    /// Write a complete RON document for a struct with the root type's name
+    fn write_document(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        fields: &[FieldInfo],
+    ) -> fmt::Result {
+        writeln!(buffer, "{}", HEADER)?;
+        writeln!(buffer, "{}(", ctx.name.map(struct_name).unwrap_or_default())?;
+        write_fields(buffer, fields, 4, ctx.defaults)?;
+        writeln!(buffer, ")")
+    }
+
+    // RON keeps enum variant names that a value tree can't
+    fn deserialize(
+        &self,
+        input: &str,
+        _schema: Option<&ConfigSchema>,
+        visit: &mut Visit<'_>,
+    ) -> Result<(), String> {
+        let mut deserializer = ::ron::Deserializer::from_str(input).map_err(|e| e.to_string())?;
+        visit_deserializer(visit, &mut deserializer)?;
+        deserializer.end().map_err(|e| e.to_string())
+    }
+}
+
+/// Write a nested section as a named struct field
+fn write_section(
+    buffer: &mut String,
+    field: &FieldInfo,
+    indent: usize,
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    let pad = " ".repeat(indent);
+
+    writeln!(
+        buffer,
+        "{}{}: {}(",
+        pad,
+        field.name,
+        struct_name(&field.field_type)
+    )?;
+    write_fields(buffer, &field.nested_fields, indent + 4, defaults)?;
+    writeln!(buffer, "{}),", pad)
+}
+
+/// Write the fields of a struct, separated by blank lines
+fn write_fields(
+    buffer: &mut String,
+    fields: &[FieldInfo],
+    indent: usize,
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    let pad = " ".repeat(indent);
+    let marker = format!("{}//", pad);
+
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            writeln!(buffer)?;
+        }
+
+        write_comment_lines(buffer, field.doc_comments.as_deref(), &marker)?;
+
+        if field.is_nested {
+            write_section(buffer, field, indent, defaults)?;
+            continue;
+        }
+
+        let key = resolve_key(field, defaults);
+        write_annotation(buffer, key.annotation.as_ref(), &marker, &Ron)?;
+
+        let value = format_value(&key.value);
+        if key.commented {
+            writeln!(buffer, "{}// {}: {},", pad, field.name, value)?;
+        } else {
+            writeln!(buffer, "{}{}: {},", pad, field.name, value)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Render a value in RON syntax on a single line
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => "None".to_string(),
+        Value::Array(items) => format!("[{}]", join(items.iter().map(format_value))),
+        Value::Table(entries) => format!(
+            "{{{}}}",
+            join(entries.iter().map(|(key, value)| {
+                format!(
+                    "{}: {}",
+                    format_value(&Value::String(key.clone())),
+                    format_value(value)
+                )
+            }))
+        ),
+        Value::Struct(name, entries) => format!("{}({})", name, struct_fields(entries)),
+        Value::Variant(name, data) => match data.as_ref() {
+            VariantData::Unit => name.clone(),
+            VariantData::Newtype(value) => format!("{}({})", name, format_value(value)),
+            VariantData::Tuple(items) => {
+                format!("{}({})", name, join(items.iter().map(format_value)))
+            }
+            VariantData::Struct(entries) => format!("{}({})", name, struct_fields(entries)),
+        },
+        scalar => ::ron::to_string(scalar).unwrap(),
+    }
+}
+
+fn struct_fields(entries: &[(String, Value)]) -> String {
+    join(
+        entries
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, format_value(value))),
+    )
+}
+
+fn join(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<_>>().join(", ")
+}
+
+/// The bare struct name of a type, e.g. `Server` for `crate::config::Server`
+fn struct_name(field_type: &str) -> &str {
+    let base = field_type.split('<').next().unwrap_or(field_type);
+    base.rsplit("::").next().unwrap_or(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_example, ConfigFormat, ExampleOptions};
+
+    use serde::Deserialize;
+
+    fn fields() -> Vec<FieldInfo> {
+        vec![
+            FieldInfo::new("mode")
+                .field_type("Mode")
+                .default(Value::Variant(
+                    "Tls".to_string(),
+                    Box::new(VariantData::Newtype(Value::from("cert.pem"))),
+                )),
+            FieldInfo::new("server")
+                .field_type("crate::config::Server")
+                .nested(vec![
+                    FieldInfo::new("host")
+                        .doc("Host")
+                        .field_type("String")
+                        .default(Value::from("localhost")),
+                    FieldInfo::new("timeout")
+                        .field_type("Option<u64>")
+                        .example(Value::Integer(30)),
+                ]),
+        ]
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Config {
+        mode: Mode,
+        server: Server,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Mode {
+        Tls(String),
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Server {
+        host: String,
+        timeout: Option<u64>,
+    }
+
+    #[test]
+    fn sections_are_named_structs() {
+        let example = generate_example(&fields(), &ExampleOptions::new(ConfigFormat::Ron));
+        assert_eq!(
+            example,
+            r#"#![enable(implicit_some)]
+(
+    // Default: Tls("cert.pem")
+    mode: Tls("cert.pem"),
+
+    server: Server(
+        // Host
+        // Default: "localhost"
+        host: "localhost",
+
+        // Optional
+        timeout: 30,
+    ),
+)
+"#
+        );
+
+        // `implicit_some` reads the plain `30` as `Some(30)`
+        let config: Config = ConfigFormat::Ron.parse(&example).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                mode: Mode::Tls("cert.pem".to_string()),
+                server: Server {
+                    host: "localhost".to_string(),
+                    timeout: Some(30),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn values_use_native_syntax() {
+        let variant = |name: &str, data| Value::Variant(name.to_string(), Box::new(data));
+        let cases = [
+            (Value::Null, "None"),
+            (variant("Plain", VariantData::Unit), "Plain"),
+            (
+                variant(
+                    "Pair",
+                    VariantData::Tuple(vec![Value::Integer(1), Value::Integer(2)]),
+                ),
+                "Pair(1, 2)",
+            ),
+            (
+                variant(
+                    "Limits",
+                    VariantData::Struct(vec![("max".to_string(), Value::Float(1.5))]),
+                ),
+                "Limits(max: 1.5)",
+            ),
+            (
+                Value::Table(vec![("a b".to_string(), Value::Bool(true))]),
+                r#"{"a b": true}"#,
+            ),
+            (Value::from("say \"hi\""), r#""say \"hi\"""#),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(format_value(&value), expected);
+        }
+    }
+
+    #[test]
+    fn struct_names_drop_paths_and_generics() {
+        assert_eq!(struct_name("crate::config::Server"), "Server");
+        assert_eq!(struct_name("Wrapper<crate::Inner>"), "Wrapper");
+        assert_eq!(struct_name(""), "");
+    }
+}
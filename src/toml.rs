@@ -0,0 +1,119 @@
+// serde_config_docs/src/toml.rs
+//! TOML, the default format
+//!
+//! TOML is the layout the [`Renderer`] defaults are modeled on, so this only
+//! supplies quoting and the serde round trip.
+
+use crate::render::{visit_deserializer, Renderer, Visit};
+use crate::{ConfigFormat, ConfigSchema, ShapeError, Value};
+
+use std::fmt::{self, Write};
+
+#[derive(Debug)]
+pub(crate) struct Toml;
+
+impl Renderer for Toml {
+    fn name(&self) -> &str {
+        "toml"
+    }
+
+    fn extension(&self) -> &str {
+        "toml"
+    }
+
+    fn comment_marker(&self) -> Option<&str> {
+        Some("#")
+    }
+
+    // Inline values keep multi-line strings on the key's line
+    fn render_value(&self, value: &Value) -> String {
+        toml_inline(value)
+    }
+
+    fn render_entry(&self, key: &str, value: &Value) -> String {
+        format!("{} = {}", toml_key(key), toml_inline(value))
+    }
+
+    fn render_document(&self, value: &Value) -> Result<String, ShapeError> {
+        ::toml::to_string(value).map_err(|err| ShapeError {
+            format: ConfigFormat::Toml,
+            path: String::new(),
+            reason: err.to_string(),
+        })
+    }
+
+    fn write_section_header(&self, buffer: &mut String, path: &[&str]) -> fmt::Result {
+        let path: Vec<String> = path.iter().map(|key| toml_key(key)).collect();
+        writeln!(buffer, "[{}]", path.join("."))?;
+        writeln!(buffer)
+    }
+
+    fn deserialize(
+        &self,
+        input: &str,
+        _schema: Option<&ConfigSchema>,
+        visit: &mut Visit<'_>,
+    ) -> Result<(), String> {
+        let deserializer = ::toml::Deserializer::new(input);
+        visit_deserializer(visit, deserializer)
+    }
+}
+
+/// Quote a TOML key unless it's a valid bare key
+fn toml_key(name: &str) -> String {
+    let is_bare = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+    if is_bare {
+        name.to_string()
+    } else {
+        toml_inline(&Value::String(name.to_string()))
+    }
+}
+
+/// Render a value as inline TOML, which always fits on one line
+pub(crate) fn toml_inline(value: &Value) -> String {
+    match value {
+        Value::Null => "\"\"".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) if f.is_nan() => "nan".to_string(),
+        Value::Float(f) => format!("{:?}", f),
+        Value::String(text) => {
+            let mut quoted = String::from("\"");
+            for c in text.chars() {
+                match c {
+                    '"' => quoted.push_str("\\\""),
+                    '\\' => quoted.push_str("\\\\"),
+                    '\n' => quoted.push_str("\\n"),
+                    '\r' => quoted.push_str("\\r"),
+                    '\t' => quoted.push_str("\\t"),
+                    c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+                    c => quoted.push(c),
+                }
+            }
+            quoted.push('"');
+            quoted
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(toml_inline).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Table(entries) | Value::Struct(_, entries) => {
+            // TOML has no null, so absent values are left out like serde does
+            let entries: Vec<String> = entries
+                .iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| format!("{} = {}", toml_key(key), toml_inline(value)))
+                .collect();
+            if entries.is_empty() {
+                "{}".to_string()
+            } else {
+                format!("{{ {} }}", entries.join(", "))
+            }
+        }
+        Value::Variant(..) => toml_inline(&value.externally_tagged()),
+    }
+}
@@ -0,0 +1,966 @@
+// serde_config_docs/src/value.rs
+//! A format-independent representation of configuration values
+//!
+//! Default values are captured once, at schema construction time, by running
+//! them through serde into a [`Value`]. Each output format then renders the
+//! same `Value` in its own syntax.
+
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Unexpected, Visitor};
+use serde::ser::{self, Serialize};
+use std::fmt;
+
+/// A serialized configuration value, independent of any output format
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// An absent value, such as `Option::None` or `()`
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Value>),
+    /// A map, with entries kept in insertion order
+    Table(Vec<(String, Value)>),
+    /// A struct and its type name, with fields kept in declaration order
+    Struct(String, Vec<(String, Value)>),
+    /// An enum variant, kept apart from strings and maps so that formats
+    /// with native enum syntax can write it as such
+    Variant(String, Box<VariantData>),
+}
+
+/// The data carried by an enum variant
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariantData {
+    Unit,
+    Newtype(Value),
+    Tuple(Vec<Value>),
+    Struct(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Whether this value can't be written in formats without a null literal
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// Rewrite enum variants the way serde writes them to self-describing
+    /// formats: unit variants as strings, others as `{ variant = data }`
+    pub fn externally_tagged(&self) -> Value {
+        match self {
+            Value::Variant(name, data) => match data.as_ref() {
+                VariantData::Unit => Value::String(name.clone()),
+                VariantData::Newtype(value) => {
+                    Value::Table(vec![(name.clone(), value.externally_tagged())])
+                }
+                VariantData::Tuple(items) => Value::Table(vec![(
+                    name.clone(),
+                    Value::Array(items.iter().map(Value::externally_tagged).collect()),
+                )]),
+                VariantData::Struct(entries) => {
+                    Value::Table(vec![(name.clone(), Value::Table(tagged_entries(entries)))])
+                }
+            },
+            Value::Array(items) => {
+                Value::Array(items.iter().map(Value::externally_tagged).collect())
+            }
+            Value::Table(entries) => Value::Table(tagged_entries(entries)),
+            Value::Struct(name, entries) => Value::Struct(name.clone(), tagged_entries(entries)),
+            other => other.clone(),
+        }
+    }
+}
+
+fn tagged_entries(entries: &[(String, Value)]) -> Vec<(String, Value)> {
+    entries
+        .iter()
+        .map(|(key, value)| (key.clone(), value.externally_tagged()))
+        .collect()
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use ser::{SerializeMap, SerializeSeq};
+
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Integer(i) => serializer.serialize_i64(*i),
+            Value::Float(f) => serializer.serialize_f64(*f),
+            Value::String(s) => serializer.serialize_str(s),
+            Value::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Value::Table(entries) | Value::Struct(_, entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Value::Variant(..) => self.externally_tagged().serialize(serializer),
+        }
+    }
+}
+
+/// Convert any serializable value into a [`Value`]
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, ValueError> {
+    value.serialize(ValueSerializer)
+}
+
+/// Deserialize a `T` from a [`Value`]
+///
+/// Strings are parsed on demand wherever another type is expected, since flat
+/// formats like INI don't tell `8080` and `"8080"` apart. For the same reason
+/// a comma-separated string can stand in for a list.
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, ValueError> {
+    T::deserialize(value)
+}
+
+/// Error returned when a value can't be represented as a [`Value`], or a
+/// [`Value`] can't be deserialized into a type
+#[derive(Debug, Clone)]
+pub struct ValueError(String);
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ValueError {}
+
+impl ser::Error for ValueError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ValueError(msg.to_string())
+    }
+}
+
+impl de::Error for ValueError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ValueError(msg.to_string())
+    }
+}
+
+/// Serializer that builds a [`Value`] tree
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = ValueError;
+
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeVec;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeVariant<SerializeVec>;
+    type SerializeMap = SerializeTable;
+    type SerializeStruct = SerializeTable;
+    type SerializeStructVariant = SerializeVariant<SerializeTable>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, ValueError> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, ValueError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, ValueError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, ValueError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, ValueError> {
+        Ok(Value::Integer(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value, ValueError> {
+        i64::try_from(v)
+            .map(Value::Integer)
+            .map_err(|_| ValueError(format!("integer {} is out of range", v)))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, ValueError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, ValueError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, ValueError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, ValueError> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value, ValueError> {
+        i64::try_from(v)
+            .map(Value::Integer)
+            .map_err(|_| ValueError(format!("integer {} is out of range", v)))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, ValueError> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, ValueError> {
+        Ok(Value::Float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, ValueError> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, ValueError> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, ValueError> {
+        Ok(Value::Array(
+            v.iter().map(|b| Value::Integer((*b).into())).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> Result<Value, ValueError> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, ValueError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, ValueError> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, ValueError> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value, ValueError> {
+        Ok(Value::Variant(
+            variant.to_string(),
+            Box::new(VariantData::Unit),
+        ))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, ValueError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, ValueError> {
+        Ok(Value::Variant(
+            variant.to_string(),
+            Box::new(VariantData::Newtype(to_value(value)?)),
+        ))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeVec, ValueError> {
+        Ok(SerializeVec {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeVec, ValueError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeVec, ValueError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, ValueError> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeTable, ValueError> {
+        Ok(SerializeTable {
+            struct_name: None,
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<SerializeTable, ValueError> {
+        Ok(SerializeTable {
+            struct_name: Some(name),
+            entries: Vec::with_capacity(len),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, ValueError> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+struct SerializeVec {
+    items: Vec<Value>,
+}
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        self.items.push(to_value(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        Ok(Value::Array(self.items))
+    }
+}
+
+impl ser::SerializeTuple for SerializeVec {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeVec {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+struct SerializeTable {
+    struct_name: Option<&'static str>,
+    entries: Vec<(String, Value)>,
+    next_key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeTable {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), ValueError> {
+        let key = match to_value(key)? {
+            Value::String(s) => s,
+            Value::Bool(b) => b.to_string(),
+            Value::Integer(i) => i.to_string(),
+            Value::Variant(name, data) if *data == VariantData::Unit => name,
+            other => return Err(ValueError(format!("unsupported map key {:?}", other))),
+        };
+        self.next_key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| ValueError("map value serialized before its key".to_string()))?;
+        self.entries.push((key, to_value(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        Ok(Value::Table(self.entries))
+    }
+}
+
+impl ser::SerializeStruct for SerializeTable {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ValueError> {
+        self.entries.push((key.to_string(), to_value(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        match self.struct_name {
+            Some(name) => Ok(Value::Struct(name.to_string(), self.entries)),
+            None => Ok(Value::Table(self.entries)),
+        }
+    }
+}
+
+/// Collects the data of a tuple or struct enum variant
+struct SerializeVariant<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeVec> {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ValueError> {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        Ok(Value::Variant(
+            self.variant.to_string(),
+            Box::new(VariantData::Tuple(self.inner.items)),
+        ))
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeTable> {
+    type Ok = Value;
+    type Error = ValueError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ValueError> {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Value, ValueError> {
+        Ok(Value::Variant(
+            self.variant.to_string(),
+            Box::new(VariantData::Struct(self.inner.entries)),
+        ))
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ValueError> for Value {
+    type Deserializer = Value;
+
+    fn into_deserializer(self) -> Value {
+        self
+    }
+}
+
+/// Deserialize scalars from their string form when a string is all we have
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident($ty:ty),)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+                match self {
+                    Value::String(s) => match s.trim().parse::<$ty>() {
+                        Ok(parsed) => visitor.$visit(parsed),
+                        Err(_) => Err(de::Error::invalid_value(Unexpected::Str(&s), &visitor)),
+                    },
+                    other => other.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Integer(i) => visitor.visit_i64(i),
+            Value::Float(f) => visitor.visit_f64(f),
+            Value::String(s) => visitor.visit_string(s),
+            Value::Array(items) => visit_array(items, visitor),
+            Value::Table(entries) | Value::Struct(_, entries) => visit_table(entries, visitor),
+            variant @ Value::Variant(..) => variant.externally_tagged().deserialize_any(visitor),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool(bool),
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_f32 => visit_f32(f32),
+        deserialize_f64 => visit_f64(f64),
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            Value::Bool(b) => visitor.visit_string(b.to_string()),
+            Value::Integer(i) => visitor.visit_string(i.to_string()),
+            Value::Float(f) => visitor.visit_string(f.to_string()),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            Value::Null => visitor.visit_none(),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            // Formats like KDL write empty lists and maps the same way
+            Value::String(s) if s.trim().is_empty() => visit_array(Vec::new(), visitor),
+            Value::Table(entries) if entries.is_empty() => visit_array(Vec::new(), visitor),
+            // HCL reads a single repeated block as one object
+            table @ Value::Table(_) => visit_array(vec![table], visitor),
+            Value::String(s) => {
+                let items = s
+                    .split(',')
+                    .map(|item| Value::String(item.trim().to_string()))
+                    .collect();
+                visit_array(items, visitor)
+            }
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            Value::String(s) if s.trim().is_empty() => visit_table(Vec::new(), visitor),
+            Value::Array(items) if items.is_empty() => visit_table(Vec::new(), visitor),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        let (variant, data) = match self {
+            Value::String(variant) => (variant, None),
+            Value::Variant(variant, data) => {
+                let data = match *data {
+                    VariantData::Unit => None,
+                    VariantData::Newtype(value) => Some(value),
+                    VariantData::Tuple(items) => Some(Value::Array(items)),
+                    VariantData::Struct(entries) => Some(Value::Table(entries)),
+                };
+                (variant, data)
+            }
+            Value::Table(entries) if entries.len() == 1 => {
+                let (variant, data) = entries.into_iter().next().unwrap();
+                (variant, Some(data))
+            }
+            other => {
+                return Err(ValueError(format!(
+                    "expected an enum variant, found {:?}",
+                    other
+                )))
+            }
+        };
+
+        visitor.visit_enum(EnumDeserializer { variant, data })
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        char bytes byte_buf unit unit_struct
+    }
+}
+
+fn visit_array<'de, V: Visitor<'de>>(
+    items: Vec<Value>,
+    visitor: V,
+) -> Result<V::Value, ValueError> {
+    let mut seq = de::value::SeqDeserializer::new(items.into_iter());
+    let value = visitor.visit_seq(&mut seq)?;
+    seq.end()?;
+    Ok(value)
+}
+
+fn visit_table<'de, V: Visitor<'de>>(
+    entries: Vec<(String, Value)>,
+    visitor: V,
+) -> Result<V::Value, ValueError> {
+    let mut map = de::value::MapDeserializer::new(entries.into_iter());
+    let value = visitor.visit_map(&mut map)?;
+    map.end()?;
+    Ok(value)
+}
+
+/// Gives serde access to an enum variant and its data
+struct EnumDeserializer {
+    variant: String,
+    data: Option<Value>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = ValueError;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<S: de::DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<(S::Value, VariantDeserializer), ValueError> {
+        let variant = seed.deserialize(Value::String(self.variant))?;
+        Ok((variant, VariantDeserializer { data: self.data }))
+    }
+}
+
+struct VariantDeserializer {
+    data: Option<Value>,
+}
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
+    type Error = ValueError;
+
+    fn unit_variant(self) -> Result<(), ValueError> {
+        match self.data {
+            None | Some(Value::Null) => Ok(()),
+            Some(other) => Err(ValueError(format!(
+                "expected a unit variant, found {:?}",
+                other
+            ))),
+        }
+    }
+
+    fn newtype_variant_seed<S: de::DeserializeSeed<'de>>(
+        self,
+        seed: S,
+    ) -> Result<S::Value, ValueError> {
+        seed.deserialize(self.data.unwrap_or(Value::Null))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        de::Deserializer::deserialize_seq(self.data.unwrap_or(Value::Null), visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        de::Deserializer::deserialize_map(self.data.unwrap_or(Value::Null), visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Server {
+        host: String,
+        port: u16,
+        timeout: Option<f64>,
+        mode: Mode,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Mode {
+        Plain,
+        Tls(String),
+        Pair(u8, u8),
+        Limits { max: u32 },
+    }
+
+    fn variant(name: &str, data: VariantData) -> Value {
+        Value::Variant(name.to_string(), Box::new(data))
+    }
+
+    #[test]
+    fn structs_keep_their_name_and_field_order() {
+        let server = Server {
+            host: "localhost".to_string(),
+            port: 8080,
+            timeout: None,
+            mode: Mode::Plain,
+        };
+        assert_eq!(
+            to_value(&server).unwrap(),
+            Value::Struct(
+                "Server".to_string(),
+                vec![
+                    ("host".to_string(), Value::from("localhost")),
+                    ("port".to_string(), Value::Integer(8080)),
+                    ("timeout".to_string(), Value::Null),
+                    ("mode".to_string(), variant("Plain", VariantData::Unit)),
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn enum_variants_keep_their_data() {
+        let modes = [
+            Mode::Tls("cert.pem".to_string()),
+            Mode::Pair(1, 2),
+            Mode::Limits { max: 10 },
+        ];
+        assert_eq!(
+            to_value(&modes).unwrap(),
+            Value::Array(vec![
+                variant("Tls", VariantData::Newtype(Value::from("cert.pem"))),
+                variant(
+                    "Pair",
+                    VariantData::Tuple(vec![Value::Integer(1), Value::Integer(2)])
+                ),
+                variant(
+                    "Limits",
+                    VariantData::Struct(vec![("max".to_string(), Value::Integer(10))])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn externally_tagged_variants_look_like_serde_writes_them() {
+        let value = to_value(&[Mode::Plain, Mode::Limits { max: 10 }]).unwrap();
+        let limits = Value::Table(vec![("max".to_string(), Value::Integer(10))]);
+        assert_eq!(
+            value.externally_tagged(),
+            Value::Array(vec![
+                Value::from("Plain"),
+                Value::Table(vec![("Limits".to_string(), limits)]),
+            ])
+        );
+    }
+
+    #[test]
+    fn maps_keep_their_order_and_reject_integers_that_dont_fit() {
+        let map: BTreeMap<&str, u8> = [("b", 2), ("a", 1)].into_iter().collect();
+        assert_eq!(
+            to_value(&map).unwrap(),
+            Value::Table(vec![
+                ("a".to_string(), Value::Integer(1)),
+                ("b".to_string(), Value::Integer(2)),
+            ])
+        );
+        assert!(to_value(&u64::MAX).is_err());
+    }
+
+    fn table(entries: &[(&str, Value)]) -> Value {
+        Value::Table(
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn strings_are_parsed_where_scalars_are_expected() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Flat {
+            host: String,
+            port: u16,
+            timeout: Option<f64>,
+            mode: Mode,
+        }
+
+        let value = table(&[
+            ("host", Value::from("localhost")),
+            ("port", Value::from(" 8080 ")),
+            ("timeout", Value::from("2.5")),
+            ("mode", Value::from("Plain")),
+        ]);
+
+        assert_eq!(
+            from_value::<Flat>(value).unwrap(),
+            Flat {
+                host: "localhost".to_string(),
+                port: 8080,
+                timeout: Some(2.5),
+                mode: Mode::Plain,
+            }
+        );
+
+        let err = from_value::<u16>(Value::from("eighty")).unwrap_err();
+        assert!(err.to_string().contains("eighty"), "{}", err);
+        // Scalars can stand in for strings too
+        assert_eq!(from_value::<String>(Value::Integer(3)).unwrap(), "3");
+        assert!(from_value::<bool>(Value::from("true")).unwrap());
+    }
+
+    #[test]
+    fn lists_can_be_written_in_other_shapes() {
+        let list = |value| from_value::<Vec<String>>(value).unwrap();
+        assert_eq!(list(Value::from("a, b,c")), ["a", "b", "c"]);
+        assert!(list(Value::from(" ")).is_empty());
+        assert!(list(Value::Table(Vec::new())).is_empty());
+        assert_eq!(from_value::<Vec<u8>>(Value::from("1, 2")).unwrap(), [1, 2]);
+
+        // A block that's written once reads as a table instead of a list
+        let servers =
+            from_value::<Vec<BTreeMap<String, String>>>(table(&[("host", Value::from("a"))]))
+                .unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0]["host"], "a");
+
+        assert!(from_value::<BTreeMap<String, u8>>(Value::from(""))
+            .unwrap()
+            .is_empty());
+        assert!(from_value::<BTreeMap<String, u8>>(Value::Array(Vec::new()))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn enums_are_read_from_every_shape() {
+        let modes = [
+            Mode::Plain,
+            Mode::Tls("cert.pem".to_string()),
+            Mode::Pair(1, 2),
+            Mode::Limits { max: 10 },
+        ];
+        for mode in modes {
+            let value = to_value(&mode).unwrap();
+            let tagged = value.externally_tagged();
+            assert_eq!(from_value::<Mode>(value).unwrap(), mode);
+            assert_eq!(from_value::<Mode>(tagged).unwrap(), mode);
+        }
+
+        let err = from_value::<Mode>(Value::Integer(1)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected an enum variant, found Integer(1)"
+        );
+        assert!(from_value::<Mode>(Value::from("Unknown")).is_err());
+    }
+}
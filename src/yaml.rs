@@ -0,0 +1,380 @@
+// serde_config_docs/src/yaml.rs
+//! YAML, with nested sections written as indented mappings
+//!
+//! Values are written in flow style, which always fits on the key's line, and
+//! enums are written as YAML tags the way serde_yaml reads them.
+
+use crate::render::{
+    resolve_key, visit_deserializer, write_annotation, write_comment_lines, RenderContext,
+    Renderer, Visit,
+};
+use crate::{ConfigFormat, ConfigSchema, DefaultsStyle, FieldInfo, ShapeError, Value, VariantData};
+
+use std::fmt::{self, Write};
+
+#[derive(Debug)]
+pub(crate) struct Yaml;
+
+impl Renderer for Yaml {
+    fn name(&self) -> &str {
+        "yaml"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["yml"]
+    }
+
+    fn extension(&self) -> &str {
+        "yaml"
+    }
+
+    fn comment_marker(&self) -> Option<&str> {
+        Some("#")
+    }
+
+    // Flow style keeps compound values on the key's line
+    fn render_value(&self, value: &Value) -> String {
+        yaml_flow(value)
+    }
+
+    fn render_document(&self, value: &Value) -> Result<String, ShapeError> {
+        serde_yaml::to_string(value).map_err(|err| ShapeError {
+            format: ConfigFormat::Yaml,
+            path: String::new(),
+            reason: err.to_string(),
+        })
+    }
+
+    fn nests_sections(&self) -> bool {
+        true
+    }
+
+    fn write_section(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        section: &FieldInfo,
+        _path: &[&str],
+    ) -> fmt::Result {
+        write_yaml_section(buffer, section, 0, ctx.defaults)
+    }
+
+    fn write_document(
+        &self,
+        buffer: &mut String,
+        ctx: &RenderContext<'_>,
+        fields: &[FieldInfo],
+    ) -> fmt::Result {
+        // A document with only comments reads as an empty struct, so it
+        // needs nothing that would break once a key is uncommented
+        write_yaml_mapping(buffer, fields, 0, ctx.defaults)
+    }
+
+    fn deserialize(
+        &self,
+        input: &str,
+        _schema: Option<&ConfigSchema>,
+        visit: &mut Visit<'_>,
+    ) -> Result<(), String> {
+        let deserializer = serde_yaml::Deserializer::from_str(input);
+        visit_deserializer(visit, deserializer)
+    }
+}
+
+/// Write the entries of a YAML mapping, nesting sections by indentation
+fn write_yaml_mapping(
+    buffer: &mut String,
+    fields: &[FieldInfo],
+    indent: usize,
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    let pad = " ".repeat(indent);
+    let marker = format!("{}#", pad);
+
+    for field in fields {
+        write_comment_lines(buffer, field.doc_comments.as_deref(), &marker)?;
+
+        if field.is_nested {
+            write_yaml_section(buffer, field, indent, defaults)?;
+            continue;
+        }
+
+        let key = resolve_key(field, defaults);
+        write_annotation(buffer, key.annotation.as_ref(), &marker, &Yaml)?;
+
+        let name = yaml_flow(&Value::String(field.name.clone()));
+        let value = yaml_flow(&key.value);
+        if key.commented {
+            writeln!(buffer, "{}# {}: {}", pad, name, value)?;
+        } else {
+            writeln!(buffer, "{}{}: {}", pad, name, value)?;
+        }
+        writeln!(buffer)?;
+    }
+
+    Ok(())
+}
+
+/// Write a nested section as a key holding an indented mapping
+fn write_yaml_section(
+    buffer: &mut String,
+    field: &FieldInfo,
+    indent: usize,
+    defaults: DefaultsStyle,
+) -> fmt::Result {
+    let name = yaml_flow(&Value::String(field.name.clone()));
+
+    // A key with only commented-out entries below it is null, which serde_yaml
+    // reads as an empty struct, and stays valid when one is uncommented
+    writeln!(buffer, "{}{}:", " ".repeat(indent), name)?;
+    write_yaml_mapping(buffer, &field.nested_fields, indent + 2, defaults)
+}
+
+/// Render a value in YAML flow style, which always fits on one line
+fn yaml_flow(value: &Value) -> String {
+    match value {
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(yaml_flow).collect();
+            format!("[{}]", items.join(", "))
+        }
+        // serde_yaml writes enum variants as YAML tags, e.g. `!Custom 3`
+        Value::Variant(name, data) => match data.as_ref() {
+            VariantData::Unit => yaml_flow(&Value::String(name.clone())),
+            VariantData::Newtype(value) => format!("!{} {}", name, yaml_flow(value)),
+            VariantData::Tuple(items) => {
+                format!("!{} {}", name, yaml_flow(&Value::Array(items.clone())))
+            }
+            VariantData::Struct(entries) => {
+                format!("!{} {}", name, yaml_flow(&Value::Table(entries.clone())))
+            }
+        },
+        Value::Table(entries) | Value::Struct(_, entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| {
+                    format!(
+                        "{}: {}",
+                        yaml_flow(&Value::String(key.clone())),
+                        yaml_flow(value)
+                    )
+                })
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        }
+        Value::String(text) => {
+            let yaml = serde_yaml::to_string(text).unwrap();
+            let yaml = yaml.trim_end();
+
+            // Multi-line strings come out as block scalars, and plain scalars
+            // can't contain flow indicators, so quote those ourselves
+            let is_quoted = yaml.starts_with(['\'', '"']);
+            if yaml.contains('\n') || (!is_quoted && yaml.contains([',', '[', ']', '{', '}'])) {
+                yaml_double_quoted(text)
+            } else if !is_quoted && is_yaml_11_bool(yaml) {
+                // Older YAML 1.1 parsers still read these as booleans
+                format!("'{}'", yaml)
+            } else {
+                yaml.to_string()
+            }
+        }
+        scalar => serde_yaml::to_string(scalar)
+            .unwrap()
+            .trim_end()
+            .to_string(),
+    }
+}
+
+/// Whether a plain scalar is a boolean in YAML 1.1, e.g. `yes` or `Off`
+fn is_yaml_11_bool(plain: &str) -> bool {
+    matches!(
+        plain.to_lowercase().as_str(),
+        "y" | "n" | "yes" | "no" | "on" | "off"
+    )
+}
+
+/// Write a string as a double-quoted YAML scalar
+fn yaml_double_quoted(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_example, ConfigFormat, ExampleOptions};
+
+    use serde::Deserialize;
+
+    fn fields() -> Vec<FieldInfo> {
+        vec![
+            FieldInfo::new("port")
+                .doc("Port")
+                .field_type("u16")
+                .default(Value::Integer(8080)),
+            FieldInfo::new("mode")
+                .field_type("Mode")
+                .default(Value::Variant(
+                    "Tls".to_string(),
+                    Box::new(VariantData::Newtype(Value::from("cert.pem"))),
+                )),
+            FieldInfo::new("server").doc("Server settings").nested(vec![
+                FieldInfo::new("host")
+                    .field_type("String")
+                    .default(Value::from("localhost")),
+                FieldInfo::new("tls").nested(vec![FieldInfo::new("cert")
+                    .field_type("String")
+                    .required(true)]),
+            ]),
+        ]
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Config {
+        #[serde(default)]
+        port: u16,
+        mode: Option<Mode>,
+        server: Server,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Mode {
+        Tls(String),
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Server {
+        #[serde(default)]
+        host: String,
+        tls: Tls,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Tls {
+        cert: String,
+    }
+
+    #[test]
+    fn sections_nest_by_indentation() {
+        let example = generate_example(&fields(), &ExampleOptions::new(ConfigFormat::Yaml));
+        assert_eq!(
+            example,
+            "\
+# Port
+# Default: 8080
+port: 8080
+
+# Default: !Tls cert.pem
+mode: !Tls cert.pem
+
+# Server settings
+server:
+  # Default: localhost
+  host: localhost
+
+  tls:
+    # Required
+    cert: ''
+"
+        );
+
+        let config: Config = ConfigFormat::Yaml.parse(&example).unwrap();
+        assert_eq!(config.mode, Some(Mode::Tls("cert.pem".to_string())));
+        assert_eq!(config.server.host, "localhost");
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Defaults {
+        server: DefaultServer,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct DefaultServer {
+        #[serde(default)]
+        host: String,
+    }
+
+    #[test]
+    fn commented_out_keys_can_be_uncommented() {
+        let fields = [FieldInfo::new("server").nested(vec![FieldInfo::new("host")
+            .field_type("String")
+            .default(Value::from("localhost"))])];
+        let options = ExampleOptions::new(ConfigFormat::Yaml).defaults(DefaultsStyle::CommentedOut);
+        let example = generate_example(&fields, &options);
+        assert_eq!(example, "server:\n  # host: localhost\n");
+
+        let config: Defaults = ConfigFormat::Yaml.parse(&example).unwrap();
+        assert_eq!(config.server.host, "");
+        let config: Defaults = ConfigFormat::Yaml
+            .parse(&example.replace("# host", "host"))
+            .unwrap();
+        assert_eq!(config.server.host, "localhost");
+
+        // The same goes for the document itself
+        let example = generate_example(&fields[0].nested_fields, &options);
+        assert_eq!(example, "# host: localhost\n");
+
+        let server: DefaultServer = ConfigFormat::Yaml.parse(&example).unwrap();
+        assert_eq!(server.host, "");
+        let server: DefaultServer = ConfigFormat::Yaml
+            .parse(&example.replace("# host", "host"))
+            .unwrap();
+        assert_eq!(server.host, "localhost");
+    }
+
+    #[test]
+    fn strings_are_quoted_when_they_would_read_differently() {
+        let cases = [
+            ("localhost", "localhost"),
+            ("8080", "'8080'"),
+            ("yes", "'yes'"),
+            ("Off", "'Off'"),
+            ("a, b", "\"a, b\""),
+            ("[x]", "'[x]'"),
+            ("line\nbreak", "\"line\\nbreak\""),
+            ("tab\there \"quoted\"", "\"tab\\there \\\"quoted\\\"\""),
+        ];
+        for (text, expected) in cases {
+            let flow = yaml_flow(&Value::from(text));
+            assert_eq!(flow, expected);
+
+            let parsed: String = serde_yaml::from_str(&flow).unwrap();
+            assert_eq!(parsed, text);
+        }
+    }
+
+    #[test]
+    fn compound_values_stay_on_one_line() {
+        let value = Value::Table(vec![
+            (
+                "hosts".to_string(),
+                Value::Array(vec![Value::from("a"), Value::from("b, c")]),
+            ),
+            (
+                "limits".to_string(),
+                Value::Variant(
+                    "Pair".to_string(),
+                    Box::new(VariantData::Tuple(vec![
+                        Value::Integer(1),
+                        Value::Integer(2),
+                    ])),
+                ),
+            ),
+        ]);
+        assert_eq!(
+            yaml_flow(&value),
+            "{hosts: [a, \"b, c\"], limits: !Pair [1, 2]}"
+        );
+    }
+}
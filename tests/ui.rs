@@ -0,0 +1,10 @@
+// serde_config_docs/tests/ui.rs
+//! Check what `#[derive(ConfigDocs)]` accepts and the errors it gives
+#![cfg(feature = "toml")]
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
@@ -0,0 +1,10 @@
+use serde::Deserialize;
+use serde_config_docs::ConfigDocs;
+
+#[derive(Deserialize, ConfigDocs)]
+#[config_docs(export(path "docs/config.md"))]
+pub struct Config {
+    port: u16,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use serde::Deserialize;
+use serde_config_docs::ConfigDocs;
+
+#[derive(Deserialize, ConfigDocs)]
+#[config_docs(env_prefix = APP)]
+pub struct Config {
+    port: u16,
+}
+
+#[derive(Deserialize, ConfigDocs)]
+#[config_docs(env_prefix = 1)]
+pub struct Other {
+    port: u16,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use serde::Deserialize;
+use serde_config_docs::ConfigDocs;
+
+#[derive(Deserialize, ConfigDocs)]
+pub struct Config {
+    #[config_docs(leaf)]
+    mode: Mode,
+}
+
+#[derive(Deserialize)]
+enum Mode {
+    Fast,
+    Safe,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use serde::Deserialize;
+use serde_config_docs::ConfigDocs;
+
+#[derive(Deserialize, ConfigDocs)]
+#[config_docs(export(fromats = ["toml", "yaml"]))]
+pub struct Config {
+    port: u16,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use serde::Deserialize;
+use serde_config_docs::ConfigDocs;
+
+#[derive(Deserialize, ConfigDocs)]
+pub struct Config {
+    #[config_docs(exmaple = "example_port")]
+    port: u16,
+}
+
+fn example_port() -> u16 {
+    8080
+}
+
+fn main() {}
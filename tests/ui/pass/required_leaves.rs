@@ -0,0 +1,32 @@
+use serde::Deserialize;
+use serde_config_docs::{verify_example_config, ConfigDocs, ConfigFormat, ExampleOptions};
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+#[derive(Deserialize, ConfigDocs)]
+pub struct Config {
+    /// An enum has no placeholder, so it needs an example
+    #[config_docs(leaf, example = "example_mode")]
+    mode: Mode,
+    listen: SocketAddr,
+    timeout: Duration,
+    separator: char,
+}
+
+#[derive(Debug, Deserialize, serde::Serialize)]
+enum Mode {
+    Fast,
+    Safe,
+}
+
+fn example_mode() -> Mode {
+    Mode::Safe
+}
+
+fn main() {
+    let options = ExampleOptions::new(ConfigFormat::Toml);
+    if let Err(err) = verify_example_config::<Config>(&options) {
+        panic!("{}", err);
+    }
+}